@@ -0,0 +1,12 @@
+use proc_macro2::TokenStream;
+
+/// Prints `tokens`, the expansion of a macro, to stderr for its
+/// `debug_expand` option. Items are laid out by `prettyplease`; anything that
+/// doesn't parse as a file, like the statements of a `seq!` inside a function
+/// body, is printed as the raw token stream.
+pub fn debug_expand(tokens: &TokenStream) {
+    match syn::parse2::<syn::File>(tokens.clone()) {
+        Ok(file) => eprintln!("{}", prettyplease::unparse(&file)),
+        Err(_) => eprintln!("{}", tokens),
+    }
+}
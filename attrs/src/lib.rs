@@ -0,0 +1,199 @@
+// Attribute syntax shared by the macros of this workspace, so that every
+// option is written the same way wherever it appears:
+//
+//     #[name(flag, key = "string", key = 8, key = path, key(anything))]
+//     #[name = "string"]
+//
+// Several `#[name(...)]` attributes on one item are merged into one list.
+// Each macro walks the resulting `Arg`s and interprets the keys it knows,
+// using `set` to reject a key given twice.
+//
+// `warning` reports problems that should not stop the build, and
+// `debug_expand` prints an expansion for the `debug_expand` options.
+
+#![cfg_attr(feature = "nightly", feature(proc_macro_diagnostic))]
+
+#[cfg(feature = "nightly")]
+extern crate proc_macro;
+
+mod expand;
+mod warning;
+
+pub use expand::debug_expand;
+pub use warning::warning;
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream, Parser};
+use syn::punctuated::Punctuated;
+use syn::token::Paren;
+use syn::{Attribute, Ident, Lit, LitInt, LitStr, Path, Token, parenthesized};
+
+pub enum Value {
+    Flag,
+    Lit(Lit),
+    Path(Path),
+    List(Paren, TokenStream),
+}
+
+/// One `key`, `key = value` or `key(...)` entry.
+pub struct Arg {
+    pub key: Ident,
+    pub value: Value,
+}
+
+impl Arg {
+    fn error(&self, msg: &str) -> syn::Error {
+        syn::Error::new_spanned(self, msg)
+    }
+
+    pub fn flag(&self) -> syn::Result<()> {
+        match &self.value {
+            Value::Flag => Ok(()),
+            _ => Err(self.error(&format!("expected `{}` without a value", self.key))),
+        }
+    }
+
+    pub fn lit_str(&self) -> syn::Result<&LitStr> {
+        match &self.value {
+            Value::Lit(Lit::Str(lit)) => Ok(lit),
+            _ => Err(self.error(&format!("expected `{} = \"...\"`", self.key))),
+        }
+    }
+
+    pub fn lit_int(&self) -> syn::Result<&LitInt> {
+        match &self.value {
+            Value::Lit(Lit::Int(lit)) => Ok(lit),
+            _ => Err(self.error(&format!("expected `{} = N`", self.key))),
+        }
+    }
+
+    pub fn ident(&self) -> syn::Result<&Ident> {
+        match &self.value {
+            Value::Path(path) => path.get_ident().ok_or_else(|| self.error(&format!("expected `{} = name`", self.key))),
+            _ => Err(self.error(&format!("expected `{} = name`", self.key))),
+        }
+    }
+
+    /// Parses the contents of `key(...)` with `parser`.
+    pub fn list<P: Parser>(&self, parser: P) -> syn::Result<P::Output> {
+        match &self.value {
+            Value::List(_, tokens) => parser.parse2(tokens.clone()),
+            _ => Err(self.error(&format!("expected `{}(...)`", self.key))),
+        }
+    }
+}
+
+impl Parse for Arg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = input.call(Ident::parse_any)?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            if input.peek(Lit) {
+                Value::Lit(input.parse()?)
+            } else {
+                Value::Path(input.call(Path::parse_mod_style)?)
+            }
+        } else if input.peek(Paren) {
+            let content;
+            let paren = parenthesized!(content in input);
+            Value::List(paren, content.parse()?)
+        } else {
+            Value::Flag
+        };
+        Ok(Self { key, value })
+    }
+}
+
+// Errors about an `Arg` span all of it.
+impl ToTokens for Arg {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.key.to_tokens(tokens);
+        match &self.value {
+            Value::Flag => {}
+            Value::Lit(lit) => tokens.extend(quote! { = #lit }),
+            Value::Path(path) => tokens.extend(quote! { = #path }),
+            Value::List(paren, list) => paren.surround(tokens, |tokens| list.to_tokens(tokens)),
+        }
+    }
+}
+
+/// Comma-separated `Arg`s; a trailing comma is allowed.
+#[derive(Default)]
+pub struct Args(pub Vec<Arg>);
+
+impl Args {
+    /// Collects every `#[name(...)]` in `attrs`. A bare `#[name]` or
+    /// `#[name = ...]` becomes an `Arg` keyed by `name` itself.
+    pub fn from_attrs(name: &str, attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut args = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident(name)) {
+            if attr.tokens.is_empty() || attr.tokens.clone().into_iter().next().is_some_and(|tt| tt.to_string() == "=") {
+                let tokens = attr.path.to_token_stream().into_iter().chain(attr.tokens.clone());
+                args.0.push(syn::parse2(tokens.collect())?);
+            } else {
+                args.0.extend(attr.parse_args::<Self>()?.0);
+            }
+        }
+        Ok(args)
+    }
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let args = Punctuated::<Arg, Token![,]>::parse_terminated(input)?;
+        Ok(Self(args.into_iter().collect()))
+    }
+}
+
+impl IntoIterator for Args {
+    type Item = Arg;
+    type IntoIter = std::vec::IntoIter<Arg>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// Stores `value` in `slot`, or fails if `arg`'s key was already given.
+pub fn set<T>(slot: &mut Option<T>, arg: &Arg, value: T) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(syn::Error::new_spanned(&arg.key, format!("duplicate `{}`", arg.key)));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+/// Adds to `error`, about `arg` having an unknown key, a second error on the
+/// key naming the closest of `known`, if any is close enough.
+pub fn unknown(arg: &Arg, known: &[&str], mut error: syn::Error) -> syn::Error {
+    let key = arg.key.unraw().to_string();
+    let closest = known.iter().map(|name| (distance(&key, name), name)).filter(|(d, _)| *d <= key.len().max(3) / 3).min();
+    if let Some((_, name)) = closest {
+        error.combine(syn::Error::new_spanned(&arg.key, format!("did you mean `{}`?", name)));
+    }
+    error
+}
+
+// Edit distance, counting a swap of adjacent characters as one edit.
+fn distance(a: &str, b: &str) -> usize {
+    let (a, b) = (a.chars().collect::<Vec<_>>(), b.chars().collect::<Vec<_>>());
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for i in 0..=a.len() {
+        for j in 0..=b.len() {
+            d[i][j] = match (i, j) {
+                (0, _) => j,
+                (_, 0) => i,
+                _ => {
+                    let mut best = (d[i - 1][j - 1] + (a[i - 1] != b[j - 1]) as usize).min(d[i - 1][j] + 1).min(d[i][j - 1] + 1);
+                    if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                        best = best.min(d[i - 2][j - 2] + 1);
+                    }
+                    best
+                }
+            };
+        }
+    }
+    d[a.len()][b.len()]
+}
@@ -0,0 +1,26 @@
+use proc_macro2::{Span, TokenStream};
+
+/// Reports `msg` as a warning pointing at `span`. The returned tokens must be
+/// emitted as items next to the expansion.
+///
+/// With the `nightly` feature this is a real `proc_macro::Diagnostic` and the
+/// tokens are empty. On stable there is no such channel, so the tokens use a
+/// deprecated constant instead: rustc then prints the message as the note of
+/// a `deprecated` warning at the same span.
+#[cfg(feature = "nightly")]
+pub fn warning(span: Span, msg: &str) -> TokenStream {
+    proc_macro::Diagnostic::spanned(span.unwrap(), proc_macro::Level::Warning, msg).emit();
+    TokenStream::new()
+}
+
+#[cfg(not(feature = "nightly"))]
+pub fn warning(span: Span, msg: &str) -> TokenStream {
+    quote::quote_spanned! {span=>
+        const _: () = {
+            #[deprecated(note = #msg)]
+            #[allow(non_upper_case_globals)]
+            const warning: () = ();
+            warning
+        };
+    }
+}
@@ -1,6 +1,7 @@
-use proc_macro2::{Span, TokenStream};
-use quote::quote;
-use syn::{Data, DeriveInput, Ident, Variant};
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Attribute, Data, DataEnum, DeriveInput, Fields, FieldsUnnamed, Ident, LitInt, Type, Variant};
 
 struct VariantWrapper<'b>(&'b Variant);
 
@@ -10,31 +11,117 @@ impl<'b> VariantWrapper<'b> {
     }
 }
 
-fn gen(input: DeriveInput) -> syn::Result<TokenStream> {
-    let data = match input.data {
-        Data::Enum(item) => item,
-        _ => return Err(syn::Error::new_spanned(input, "not supported.")),
-    };
+fn bits_attr(attrs: &[Attribute]) -> syn::Result<Option<LitInt>> {
+    let mut bits = None;
+    for arg in attrs::Args::from_attrs("bits", attrs)? {
+        match arg.lit_int() {
+            Ok(n) if arg.key == "bits" => attrs::set(&mut bits, &arg, n.clone())?,
+            _ => return Err(syn::Error::new_spanned(arg, "expected `#[bits = N]`")),
+        }
+    }
+    Ok(bits)
+}
 
+fn bits(attrs: &[Attribute]) -> syn::Result<Option<u32>> {
+    match bits_attr(attrs)? {
+        Some(bits) => {
+            let n = bits.base10_parse::<u32>()?;
+            if !(1..=64).contains(&n) {
+                return Err(syn::Error::new_spanned(bits, "expected a width between 1 and 64"));
+            }
+            Ok(Some(n))
+        }
+        None => Ok(None),
+    }
+}
+
+fn gen(input: DeriveInput) -> syn::Result<TokenStream> {
     if !input.generics.params.is_empty() {
         return Err(syn::Error::new_spanned(input.generics, "generics not supported."));
     }
 
-    if data.variants.len().count_ones() != 1 {
-        return Err(syn::Error::new(Span::call_site(), "BitfieldSpecifier expected a number of variants which is a power of 2"));
+    match &input.data {
+        Data::Enum(data) => gen_enum(&input, data),
+        Data::Struct(data) => match &data.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => gen_newtype(&input, fields),
+            _ => Err(syn::Error::new_spanned(&input.ident, "expected an enum or a newtype struct like `struct Id(u16);`")),
+        },
+        Data::Union(..) => Err(syn::Error::new_spanned(input, "not supported.")),
     }
+}
+
+fn gen_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<TokenStream> {
+    let explicit = bits(&input.attrs)?;
+    let bits = match explicit {
+        Some(n) => n,
+        None => {
+            if data.variants.len().count_ones() != 1 {
+                return Err(syn::Error::new_spanned(&input.ident, "BitfieldSpecifier expected a number of variants which is a power of 2"));
+            }
+            data.variants.len().trailing_zeros()
+        }
+    };
 
     let variants = data.variants.iter().map(VariantWrapper).collect::<Vec<_>>();
     let vidents = variants.iter().map(VariantWrapper::ident).collect::<Vec<_>>();
 
     let ident = &input.ident;
+    let width = bits as usize;
+
+    let ranges = vidents.iter().map(|v| {
+        let msg = format!("discriminant value of `{}` out of range for {} bits", v, bits);
+        quote_spanned! { v.span() =>
+            const _: () = ::core::assert!((#ident::#v as u64 as u128) >> #bits == 0, #msg);
+        }
+    });
+
+    // Without `#[bits = N]` there are as many variants as bit patterns, so
+    // they cover all of them exactly when every discriminant fits.
+    let covered = explicit.is_none().then(|| {
+        let fits = quote! { ((0u64 #(| #ident::#vidents as u64)*) as u128) >> #bits == 0 };
+        quote_spanned! { ident.span() =>
+            const _: fn() = || {
+                fn check<T: ::bitfield::checks::AllBitPatternsCovered>() {}
+                check::<<::bitfield::checks::Check<{ #fits }> as ::bitfield::checks::Select>::Type>();
+            };
+        }
+    });
+
+    let arbitrary = cfg!(feature = "arbitrary").then(|| {
+        // An enum without variants has nothing to choose from.
+        let body = match vidents.len().checked_sub(1) {
+            Some(last) => {
+                let indices = 0..vidents.len();
+                quote! {
+                    ::core::result::Result::Ok(match u.int_in_range(0..=#last)? {
+                        #(#indices => Self::#vidents,)*
+                        _ => ::core::unreachable!(),
+                    })
+                }
+            }
+            None => quote! {
+                let _ = u;
+                ::core::result::Result::Err(::bitfield::__private::arbitrary::Error::EmptyChoose)
+            },
+        };
+        quote! {
+            impl ::bitfield::ArbitrarySpecifier for #ident {
+                fn arbitrary(u: &mut ::bitfield::__private::arbitrary::Unstructured) -> ::bitfield::__private::arbitrary::Result<Self> {
+                    #body
+                }
+            }
+        }
+    });
 
     Ok(quote! {
+        #covered
+
+        #(#ranges)*
+
+        #arbitrary
+
         impl ::bitfield::Specifier for #ident {
-            const BITS: usize = {
-                let bits = 0u64 #( | Self::#vidents as u64 )*;
-                (64 - bits.leading_zeros()) as usize
-            };
+            const BITS: usize = #width;
             type Type = Self;
 
             fn to(me: Self::Type) -> u64 {
@@ -46,8 +133,83 @@ fn gen(input: DeriveInput) -> syn::Result<TokenStream> {
                 #( const #vidents: u64 = #ident::#vidents as u64;)*
                 match they {
                     #(#vidents => Self::#vidents,)*
-                    _ => panic!(),
+                    _ => ::core::panic!(::core::concat!("{:#x} is not a discriminant of `", ::core::stringify!(#ident), "`"), they),
+                }
+            }
+
+            fn checked_from(they: u64) -> ::core::option::Option<Self::Type> {
+                #![allow(non_upper_case_globals)]
+                #( const #vidents: u64 = #ident::#vidents as u64;)*
+                match they {
+                    #(#vidents => ::core::option::Option::Some(Self::#vidents),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    })
+}
+
+// `struct Id(u16);` stores its integer in `#[bits = N]` bits, or in all of
+// them by default. A value that doesn't fit panics on store, like a `Bn`.
+fn gen_newtype(input: &DeriveInput, fields: &FieldsUnnamed) -> syn::Result<TokenStream> {
+    let inner = &fields.unnamed[0].ty;
+    match inner {
+        Type::Path(ty) if ty.qself.is_none() && ["u8", "u16", "u32", "u64"].iter().any(|i| ty.path.is_ident(i)) => {}
+        _ => return Err(syn::Error::new_spanned(inner, "expected one of `u8`, `u16`, `u32`, `u64`")),
+    }
+
+    let ident = &input.ident;
+    let (width, check) = match bits(&input.attrs)? {
+        Some(n) => {
+            let n = n as usize;
+            let msg = format!("`#[bits = {}]` is wider than `{}`", n, quote!(#inner));
+            let check = quote_spanned! { inner.span() =>
+                const _: () = ::core::assert!(#n <= <#inner>::BITS as usize, #msg);
+            };
+            (quote!(#n), Some(check))
+        }
+        None => (quote!(<#inner>::BITS as usize), None),
+    };
+
+    let arbitrary = cfg!(feature = "arbitrary").then(|| quote! {
+        impl ::bitfield::ArbitrarySpecifier for #ident {
+            fn arbitrary(u: &mut ::bitfield::__private::arbitrary::Unstructured) -> ::bitfield::__private::arbitrary::Result<Self> {
+                let max = <#inner>::MAX >> (<#inner>::BITS as usize - <Self as ::bitfield::Specifier>::BITS);
+                ::core::result::Result::Ok(#ident(u.int_in_range(0..=max)?))
+            }
+        }
+    });
+
+    Ok(quote! {
+        #check
+
+        #arbitrary
+
+        impl ::bitfield::Specifier for #ident {
+            const BITS: usize = #width;
+            type Type = Self;
+
+            fn to(me: Self::Type) -> u64 {
+                let v = me.0 as u64;
+                // Two shifts, as `BITS` may be 64.
+                if v >> (Self::BITS - 1) >> 1 != 0 {
+                    ::bitfield::__private::conversion_panic::<Self>(v, Self::BITS)
+                }
+                v
+            }
+
+            fn from(they: u64) -> Self::Type {
+                match <Self as ::bitfield::Specifier>::checked_from(they) {
+                    ::core::option::Option::Some(v) => v,
+                    ::core::option::Option::None => ::bitfield::__private::conversion_panic::<Self>(they, Self::BITS),
+                }
+            }
+
+            fn checked_from(they: u64) -> ::core::option::Option<Self::Type> {
+                if they >> (Self::BITS - 1) >> 1 != 0 {
+                    return ::core::option::Option::None;
                 }
+                ::core::option::Option::Some(#ident(they as #inner))
             }
         }
     })
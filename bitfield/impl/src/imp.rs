@@ -1,6 +1,122 @@
 use proc_macro2::TokenStream;
-use syn::{Field, Fields, FieldsNamed, ItemStruct, Type, Ident};
-use quote::{format_ident, quote};
+use syn::{Attribute, Expr, ExprLit, Field, GenericArgument, Lit, PathArguments, Fields, FieldsNamed, ItemStruct, Path, Type, TypePath, Ident, LitInt, LitStr, Token, Visibility};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::spanned::Spanned;
+
+const REPRS: &[&str] = &["u8", "u16", "u32", "u64", "u128"];
+
+const EXPECTED: &str = "expected `repr = ...`, `bytes = ...`, `bits = ...`, `derive(...)`, `arbitrary`, `atomic`, `defmt`, `serde = ...`, `unpacked`, `view`, `diff`, `masks`, `layout = ...` or `debug_expand`";
+
+#[derive(Default)]
+struct Args {
+    repr: Option<Ident>,
+    size: Option<(Ident, LitInt)>,
+    derives: Vec<Path>,
+    atomic: Option<Ident>,
+    arbitrary: Option<Ident>,
+    defmt: Option<Ident>,
+    serde: Option<LitStr>,
+    unpacked: Option<Ident>,
+    view: Option<Ident>,
+    diff: Option<Ident>,
+    layout: Option<LitStr>,
+    masks: Option<Ident>,
+    debug_expand: Option<Ident>,
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut repr = None;
+        let mut size = None;
+        let mut derives = vec![];
+        let mut atomic = None;
+        let mut arbitrary = None;
+        let mut defmt = None;
+        let mut serde = None;
+        let mut unpacked = None;
+        let mut view = None;
+        let mut diff = None;
+        let mut layout = None;
+        let mut masks = None;
+        let mut debug_expand = None;
+
+        for arg in input.parse::<attrs::Args>()? {
+            let key = &arg.key;
+            if key == "repr" {
+                let ty = arg.ident()?;
+                if !REPRS.iter().any(|r| ty == r) {
+                    return Err(syn::Error::new_spanned(ty, "expected one of `u8`, `u16`, `u32`, `u64`, `u128`"));
+                }
+                attrs::set(&mut repr, &arg, ty.clone())?;
+            } else if key == "bytes" || key == "bits" {
+                let n = arg.lit_int()?;
+                n.base10_parse::<usize>()?;
+                if size.is_some() {
+                    return Err(syn::Error::new_spanned(key, "the size is already given by `bytes` or `bits`"));
+                }
+                size = Some((key.clone(), n.clone()));
+            } else if key == "derive" {
+                derives.extend(arg.list(|input: ParseStream| input.parse_terminated::<_, Token![,]>(Path::parse_mod_style))?);
+            } else if key == "serde" {
+                let mode = arg.lit_str()?;
+                if mode.value() != "bytes" && mode.value() != "fields" {
+                    return Err(syn::Error::new_spanned(mode, r#"expected `serde = "bytes"` or `serde = "fields"`"#));
+                }
+                attrs::set(&mut serde, &arg, mode.clone())?;
+            } else if key == "layout" {
+                let mode = arg.lit_str()?;
+                if mode.value() != "packed" && mode.value() != "c" {
+                    return Err(syn::Error::new_spanned(mode, r#"expected `layout = "packed"` or `layout = "c"`"#));
+                }
+                attrs::set(&mut layout, &arg, mode.clone())?;
+            } else {
+                let flag = match key.to_string().as_str() {
+                    "atomic" => &mut atomic,
+                    "arbitrary" => &mut arbitrary,
+                    "defmt" => &mut defmt,
+                    "unpacked" => &mut unpacked,
+                    "view" => &mut view,
+                    "diff" => &mut diff,
+                    "masks" => &mut masks,
+                    "debug_expand" => &mut debug_expand,
+                    _ => return Err(syn::Error::new_spanned(key, EXPECTED)),
+                };
+                arg.flag()?;
+                attrs::set(flag, &arg, key.clone())?;
+            }
+        }
+
+        if let (None, Some(atomic)) = (&repr, &atomic) {
+            return Err(syn::Error::new_spanned(atomic, "`atomic` requires `repr = ...`"));
+        }
+        if let (None, Some(masks)) = (&repr, &masks) {
+            return Err(syn::Error::new_spanned(masks, "`masks` requires `repr = ...`"));
+        }
+        if let (Some(repr), Some(..)) = (&repr, &atomic) {
+            if repr == "u128" {
+                return Err(syn::Error::new_spanned(repr, "`atomic` is not supported for `u128`"));
+            }
+        }
+
+        Ok(Self {
+            repr,
+            size,
+            derives,
+            atomic,
+            arbitrary,
+            defmt,
+            serde,
+            unpacked,
+            view,
+            diff,
+            layout,
+            masks,
+            debug_expand,
+        })
+    }
+}
 
 struct FieldWrapper<'a>(&'a Field);
 
@@ -13,16 +129,224 @@ impl<'a> FieldWrapper<'a> {
         &self.0.ty
     }
 
+    fn vis(&self) -> &'a Visibility {
+        &self.0.vis
+    }
+
+    // Docs, cfgs and deprecations on a field carry over to its accessors.
+    fn attrs(&self) -> Vec<&'a Attribute> {
+        self.0.attrs.iter().filter(|attr| {
+            attr.path.is_ident("doc") || attr.path.is_ident("cfg") || attr.path.is_ident("deprecated")
+        }).collect()
+    }
+
+    // `#[reserved]` marks padding whose bits `eq_masked` ignores.
+    fn reserved(&self) -> bool {
+        self.0.attrs.iter().any(|attr| attr.path.is_ident("reserved"))
+    }
+
+    fn cfgs(&self) -> Vec<&'a Attribute> {
+        self.0.attrs.iter().filter(|attr| attr.path.is_ident("cfg")).collect()
+    }
+
+    // A field removed by `#[cfg]` still has its layout constants, but takes
+    // no bits, so the fields after it move up under that configuration.
+    fn size(&self) -> TokenStream {
+        let ty = self.ty();
+        self.cfg_value(quote! { <#ty as ::bitfield::Specifier>::BITS }, quote! { 0 })
+    }
+
+    // The C storage unit of a field in `layout = "c"`, in bits: the size of
+    // its `Type`, as if declared `Type name : BITS;`. `Bn<u32, 3>` is how an
+    // `unsigned name : 3;` is spelled.
+    fn unit(&self) -> TokenStream {
+        let ty = self.ty();
+        self.cfg_value(
+            quote! { ::core::mem::size_of::<<#ty as ::bitfield::Specifier>::Type>() * 8 },
+            quote! { 8 },
+        )
+    }
+
+    fn cfg_value(&self, value: TokenStream, default: TokenStream) -> TokenStream {
+        let cfgs = self.cfgs();
+        if cfgs.is_empty() {
+            return value;
+        }
+        let preds = cfgs.iter().map(|attr| &attr.tokens);
+        quote! {
+            {
+                #(#cfgs)*
+                let value = #value;
+                #[cfg(not(all(#(all #preds),*)))]
+                let value = #default;
+                value
+            }
+        }
+    }
+
     fn getter(&self) -> Ident {
         format_ident!("get_{}", self.0.ident.as_ref().unwrap())
     }
 
+    // Decoding a user-defined specifier can fail (e.g. an enum discriminant
+    // that doesn't exist), so those also get a getter with a fallback.
+    fn getter_or(&self, raw: TokenStream) -> Option<TokenStream> {
+        if self.builtin().is_some() {
+            return None;
+        }
+        let ty = self.ty();
+        let fvis = self.vis();
+        let attrs = self.attrs();
+        let getter_or = format_ident!("get_{}_or", self.0.ident.as_ref().unwrap());
+        Some(quote! {
+            #(#attrs)*
+            #fvis fn #getter_or(&self, default: <#ty as ::bitfield::Specifier>::Type) -> <#ty as ::bitfield::Specifier>::Type {
+                <#ty as ::bitfield::Specifier>::checked_from(#raw).unwrap_or(default)
+            }
+        })
+    }
+
     fn setter(&self) -> Ident {
         format_ident!("set_{}", self.0.ident.as_ref().unwrap())
     }
+
+    fn offset(&self) -> Ident {
+        format_ident!("{}_OFFSET", self.0.ident.as_ref().unwrap().unraw().to_string().to_uppercase())
+    }
+
+    fn bits(&self) -> Ident {
+        format_ident!("{}_BITS", self.0.ident.as_ref().unwrap().unraw().to_string().to_uppercase())
+    }
+
+    // Conversions of the built-in specifiers don't go through the
+    // `Specifier` trait, which keeps their accessors usable in const context.
+    fn builtin(&self) -> Option<Builtin> {
+        if let Type::Path(TypePath { qself: None, path }) = self.ty() {
+            let last = path.segments.last()?;
+            if last.ident == "Bytes" {
+                return match &last.arguments {
+                    PathArguments::AngleBracketed(..) => Some(Builtin::Bytes),
+                    _ => None,
+                };
+            }
+            if last.ident == "B" {
+                return match &last.arguments {
+                    PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+                        GenericArgument::Const(Expr::Lit(ExprLit { lit: Lit::Int(n), .. })) => {
+                            n.base10_parse::<usize>().ok().filter(|n| (1..=64).contains(n)).map(Builtin::Int)
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                };
+            }
+            if !last.arguments.is_empty() {
+                return None;
+            }
+            if path.is_ident("bool") {
+                return Some(Builtin::Bool);
+            }
+            let name = last.ident.to_string();
+            if let Some(Ok(n)) = name.strip_prefix('B').map(str::parse::<u8>) {
+                if (1..=64).contains(&n) && !name[1..].starts_with('0') {
+                    return Some(Builtin::Int(n as usize));
+                }
+            }
+        }
+        None
+    }
+
+    fn width(&self) -> Option<usize> {
+        if !self.cfgs().is_empty() {
+            return None;
+        }
+        match self.builtin()? {
+            Builtin::Int(n) => Some(n),
+            Builtin::Bool => Some(1),
+            Builtin::Bytes => None,
+        }
+    }
+
+    fn constness(&self) -> Option<Token![const]> {
+        self.builtin().map(|_| Default::default())
+    }
+
+    // A getter's conversion of the masked bits. Every value the mask lets
+    // through is valid for the built-in specifiers, so they take the
+    // unchecked path; a user-defined `from` may reject the bits and panics
+    // saying why, and `get_x_or` is its checked variant.
+    fn decode(&self, raw: TokenStream) -> TokenStream {
+        let ty = self.ty();
+        match self.builtin() {
+            Some(Builtin::Int(..)) => quote! { #raw as <#ty as ::bitfield::Specifier>::Type },
+            Some(Builtin::Bool) => quote! { #raw != 0 },
+            Some(Builtin::Bytes) => raw,
+            None => quote! { <#ty as ::bitfield::Specifier>::from(#raw) },
+        }
+    }
+
+    // The layout is fixed at compile time and fits the storage, so the
+    // accessors take the unchecked path; `read_bits` and friends, whose
+    // ranges come from the caller, take the checked one.
+    fn load(&self, owner: &Ident, data: TokenStream) -> TokenStream {
+        let (offset, bits) = (self.offset(), self.bits());
+        if let Some(Builtin::Bytes) = self.builtin() {
+            return quote! { ::bitfield::raw::load_array(#data, #owner::#offset) };
+        }
+        quote! { ::bitfield::raw::load_unchecked(#data, #owner::#offset, #owner::#bits) }
+    }
+
+    fn store(&self, owner: &Ident, data: TokenStream, val: TokenStream) -> TokenStream {
+        let (offset, bits) = (self.offset(), self.bits());
+        if let Some(Builtin::Bytes) = self.builtin() {
+            return quote! { ::bitfield::raw::store_array(#data, #owner::#offset, &#val) };
+        }
+        quote! { ::bitfield::raw::store_unchecked(#data, #owner::#offset, #owner::#bits, #val) }
+    }
+
+    fn encode(&self, val: TokenStream) -> TokenStream {
+        let ty = self.ty();
+        match self.builtin() {
+            Some(Builtin::Bytes) => val,
+            Some(..) => quote! { #val as u64 },
+            None => quote! { <#ty as ::bitfield::Specifier>::to(#val) },
+        }
+    }
 }
 
-fn gen_standard(input: &ItemStruct, fields: &FieldsNamed) -> syn::Result<TokenStream> {
+enum Builtin {
+    Int(usize),
+    Bool,
+    Bytes,
+}
+
+// Byte indices and masks of a field whose position is known at expansion,
+// reading from and writing to `self.data` directly.
+fn load_fixed(off: usize, len: usize) -> TokenStream {
+    let (begin, shift, end) = (off >> 3, off & 7, (off + len + 7) >> 3);
+    let mask = u64::MAX >> (64 - len);
+    let bytes = (begin..end).map(|i| {
+        let sh = (i - begin) << 3;
+        quote! { (self.data[#i] as u128) << #sh }
+    });
+    quote! { (((#(#bytes)|*) >> #shift) as u64 & #mask) }
+}
+
+fn store_fixed(off: usize, len: usize, val: TokenStream) -> TokenStream {
+    let (begin, shift, end) = (off >> 3, off & 7, (off + len + 7) >> 3);
+    let mask = (u64::MAX >> (64 - len)) as u128;
+    let bytes = (begin..end).map(|i| {
+        let sh = (i - begin) << 3;
+        let m = ((mask << shift) >> sh) as u8;
+        quote! { self.data[#i] = self.data[#i] & !#m | (val >> #sh) as u8 & #m; }
+    });
+    quote! {
+        let val = (#val as u128) << #shift;
+        #(#bytes)*
+    }
+}
+
+fn gen_standard(input: &ItemStruct, args: &Args, fields: &FieldsNamed) -> syn::Result<TokenStream> {
     let attrs = &input.attrs;
     let vis = &input.vis;
     let ident = &input.ident;
@@ -30,59 +354,952 @@ fn gen_standard(input: &ItemStruct, fields: &FieldsNamed) -> syn::Result<TokenSt
         return Err(syn::Error::new_spanned(&input.generics, "unsupported"));
     }
 
+    if fields.named.is_empty() {
+        return Err(syn::Error::new_spanned(fields, "#[bitfield] requires at least one field"));
+    }
+
     let fields = fields.named.iter().map(FieldWrapper::from).collect::<Vec<_>>();
-    let field_tys = fields.iter().map(FieldWrapper::ty).collect::<Vec<_>>();
-    let field_seq = (0..fields.len()).into_iter().collect::<Vec<_>>();
-    let getters = fields.iter().map(FieldWrapper::getter).collect::<Vec<_>>();
-    let setters = fields.iter().map(FieldWrapper::setter).collect::<Vec<_>>();
+    let sizes = fields.iter().map(FieldWrapper::size).collect::<Vec<_>>();
+    let c_layout = args.layout.as_ref().is_some_and(|layout| layout.value() == "c");
 
-    let len = fields.len();
-    let mut offsets = vec![];
-    let mut last = vec![];
-    for ty in &field_tys {
-        offsets.push(last.clone());
-        last.push(*ty);
+    if let (true, Some(field)) = (c_layout, fields.iter().find(|f| matches!(f.builtin(), Some(Builtin::Bytes)))) {
+        return Err(syn::Error::new_spanned(field.ty(), r#"`Bytes` fields are not supported with `layout = "c"`"#));
     }
 
+    // With `layout = "c"` the total is rounded up to the widest storage unit,
+    // like `sizeof` of the C struct.
+    let total = if c_layout {
+        let units = fields.iter().map(FieldWrapper::unit);
+        quote! {({
+            let end = 0usize;
+            let align = 8usize;
+            #(
+                let (unit, bits) = (#units, #sizes);
+                let end = ::bitfield::__private::c_place(end, bits, unit) + bits;
+                let align = if unit > align { unit } else { align };
+            )*
+            (end + align - 1) / align * align
+        })}
+    } else {
+        quote! { (0 #( + #sizes )* ) }
+    };
+
+    let declared = args.size.as_ref().map(|(unit, n)| {
+        let total = if unit == "bytes" { quote! { #n * 8 } } else { quote! { #n } };
+        let msg = format!("bitfield is declared as {} {} but its fields add up to a different size", n, unit);
+        quote_spanned! { n.span() =>
+            const _: () = ::core::assert!(#ident::__BITS == #total, #msg);
+        }
+    });
+
+    let widths = fields.iter().filter(|field| !matches!(field.builtin(), Some(Builtin::Bytes))).map(|field| {
+        let ty = field.ty();
+        let cfgs = field.cfgs();
+        let msg = format!(
+            "field `{}` must be between 1 and 64 bits wide; check `Specifier::BITS` of its type",
+            field.0.ident.as_ref().unwrap().unraw(),
+        );
+        let width = quote_spanned! { ty.span() =>
+            #(#cfgs)*
+            const _: () = ::core::assert!(
+                <#ty as ::bitfield::Specifier>::BITS >= 1 && <#ty as ::bitfield::Specifier>::BITS <= 64,
+                #msg,
+            );
+        };
+        let unit = c_layout.then(|| {
+            let msg = format!(
+                "field `{}` is wider than its C storage unit `Specifier::Type`",
+                field.0.ident.as_ref().unwrap().unraw(),
+            );
+            quote_spanned! { ty.span() =>
+                #(#cfgs)*
+                const _: () = ::core::assert!(
+                    <#ty as ::bitfield::Specifier>::BITS <= ::core::mem::size_of::<<#ty as ::bitfield::Specifier>::Type>() * 8,
+                    #msg,
+                );
+            }
+        });
+        quote! { #width #unit }
+    }).collect::<TokenStream>();
+
+    let layout = fields.iter().enumerate().map(|(i, field)| {
+        let fvis = field.vis();
+        let size = field.size();
+        let offset = field.offset();
+        let bits = field.bits();
+        let value = if let Some(prev) = i.checked_sub(1).map(|i| &fields[i]) {
+            let (prev_offset, prev_bits) = (prev.offset(), prev.bits());
+            quote! { Self::#prev_offset + Self::#prev_bits }
+        } else {
+            quote! { 0 }
+        };
+        let value = if c_layout {
+            let unit = field.unit();
+            quote! { ::bitfield::__private::c_place(#value, Self::#bits, #unit) }
+        } else {
+            value
+        };
+        quote! {
+            #fvis const #offset: usize = #value;
+            #fvis const #bits: usize = #size;
+        }
+    }).collect::<TokenStream>();
+
+    let infos = fields.iter().map(|field| {
+        let cfgs = field.cfgs();
+        let name = field.0.ident.as_ref().unwrap().unraw().to_string();
+        let (offset, bits) = (field.offset(), field.bits());
+        quote! {
+            #(#cfgs)*
+            ::bitfield::FieldInfo {
+                name: #name,
+                offset: Self::#offset,
+                bits: Self::#bits,
+            }
+        }
+    });
+
+    if let (Some(repr), Some(field)) = (&args.repr, fields.iter().find(|f| matches!(f.builtin(), Some(Builtin::Bytes)))) {
+        return Err(syn::Error::new_spanned(field.ty(), format!("`Bytes` fields are not supported with `repr = {}`", repr)));
+    }
+
+    let (repr, storage, accessors, conversions) = if let Some(repr) = &args.repr {
+        let accessors = fields.iter().map(|field| {
+            let ty = field.ty();
+            let fvis = field.vis();
+            let attrs = field.attrs();
+            let constness = field.constness();
+            let getter = field.getter();
+            let setter = field.setter();
+            let offset = field.offset();
+            let bits = field.bits();
+            let get = field.decode(quote! { (self.data >> off & mask) as u64 });
+            let val = field.encode(quote! { val });
+            let getter_or = field.getter_or(quote! {{
+                let off = Self::#offset as u32;
+                let mask = #repr::MAX >> (#repr::BITS - Self::#bits as u32);
+                (self.data >> off & mask) as u64
+            }});
+            quote! {
+                #(#attrs)*
+                #fvis #constness fn #getter(&self) -> <#ty as ::bitfield::Specifier>::Type {
+                    let off = Self::#offset as u32;
+                    let mask = #repr::MAX >> (#repr::BITS - Self::#bits as u32);
+                    #get
+                }
+                #(#attrs)*
+                #fvis #constness fn #setter(&mut self, val: <#ty as ::bitfield::Specifier>::Type) {
+                    let off = Self::#offset as u32;
+                    let mask = #repr::MAX >> (#repr::BITS - Self::#bits as u32);
+                    let val = #val as #repr & mask;
+                    self.data = self.data & !(mask << off) | val << off;
+                }
+                #getter_or
+            }
+        }).collect::<TokenStream>();
+        let conversions = quote! {
+            impl ::core::convert::From<#repr> for #ident {
+                fn from(data: #repr) -> Self {
+                    Self { data }
+                }
+            }
+
+            impl ::core::convert::From<#ident> for #repr {
+                fn from(bitfield: #ident) -> Self {
+                    bitfield.data
+                }
+            }
+
+            const _: () = ::core::assert!(
+                #ident::__BITS <= #repr::BITS as usize,
+                ::core::concat!("bitfield fields do not fit in `", ::core::stringify!(#repr), "`"),
+            );
+        };
+        (quote! { #[repr(transparent)] }, quote! { #repr }, accessors, conversions)
+    } else {
+        let mut next = (!c_layout).then_some(0);
+        let accessors = fields.iter().map(|field| {
+            let ty = field.ty();
+            let fvis = field.vis();
+            let attrs = field.attrs();
+            let constness = field.constness();
+            let getter = field.getter();
+            let setter = field.setter();
+            let val = field.encode(quote! { val });
+            let fixed = next.zip(field.width());
+            next = fixed.map(|(off, len)| off + len);
+            let (get, store) = if let Some((off, len)) = fixed {
+                (field.decode(load_fixed(off, len)), store_fixed(off, len, val))
+            } else {
+                (field.decode(field.load(ident, quote! { &self.data })), field.store(ident, quote! { &mut self.data }, val))
+            };
+            let getter_or = field.getter_or(field.load(ident, quote! { &self.data }));
+            quote! {
+                #(#attrs)*
+                #fvis #constness fn #getter(&self) -> <#ty as ::bitfield::Specifier>::Type {
+                    #get
+                }
+                #(#attrs)*
+                #fvis #constness fn #setter(&mut self, val: <#ty as ::bitfield::Specifier>::Type) {
+                    #store
+                }
+                #getter_or
+            }
+        }).collect::<TokenStream>();
+        let storage = quote! {
+            [u8; #ident::__BYTES]
+        };
+        (quote! { #[repr(C)] }, storage, accessors, quote! {})
+    };
+
+    let bytes = if args.repr.is_some() {
+        quote! { &self.data.to_le_bytes() }
+    } else {
+        quote! { &self.data }
+    };
+
+    let owned_bytes = if args.repr.is_some() {
+        quote! { self.data.to_le_bytes() }
+    } else {
+        quote! { self.data }
+    };
+    let name = ident.to_string();
+
+    let zero = if args.repr.is_some() {
+        quote! { 0 }
+    } else {
+        quote! { [0; #ident::__BYTES] }
+    };
+
+    let writes = fields.iter().map(|field| {
+        let ty = field.ty();
+        let fvis = field.vis();
+        let attrs = field.attrs();
+        let name = field.0.ident.as_ref().unwrap();
+        let offset = field.offset();
+        let bits = field.bits();
+        let val = field.encode(quote! { val });
+        let write = if let Some(Builtin::Bytes) = field.builtin() {
+            quote! {
+                let ones = [0xff; ::core::mem::size_of::<<#ty as ::bitfield::Specifier>::Type>()];
+                ::bitfield::raw::store_array(&mut self.mask, #ident::#offset, &ones);
+                ::bitfield::raw::store_array(&mut self.bits, #ident::#offset, &#val);
+            }
+        } else {
+            quote! { put(self, #ident::#offset, #ident::#bits, #val as u64); }
+        };
+        quote! {
+            #(#attrs)*
+            #fvis fn #name(&mut self, val: <#ty as ::bitfield::Specifier>::Type) -> &mut Self {
+                #write
+                self
+            }
+        }
+    });
+
+    let apply = if args.repr.is_some() {
+        quote! { self.data = self.data & !update.mask | update.bits; }
+    } else {
+        quote! {
+            for i in 0..self.data.len() {
+                self.data[i] = self.data[i] & !update.mask[i] | update.bits[i];
+            }
+        }
+    };
+
+    let put = if let Some(repr) = &args.repr {
+        quote! {
+            let off = off as u32;
+            let mask = #repr::MAX >> (#repr::BITS - bits as u32);
+            update.mask |= mask << off;
+            update.bits = update.bits & !(mask << off) | (val as #repr & mask) << off;
+        }
+    } else {
+        quote! {
+            ::bitfield::raw::store_unchecked(&mut update.mask, off, bits, u64::MAX);
+            ::bitfield::raw::store_unchecked(&mut update.bits, off, bits, val);
+        }
+    };
+
+    let update = quote! {
+        const _: () = {
+            #vis struct Update {
+                mask: #storage,
+                bits: #storage,
+            }
+
+            #[allow(dead_code)]
+            fn put(update: &mut Update, off: usize, bits: usize, val: u64) {
+                #put
+            }
+
+            impl Update {
+                #(#writes)*
+            }
+
+            impl #ident {
+                #vis fn update(&mut self, f: impl ::core::ops::FnOnce(&mut Update)) {
+                    let mut update = Update { mask: #zero, bits: #zero };
+                    f(&mut update);
+                    #apply
+                }
+            }
+        };
+    };
+
+    let defined = fields.iter().filter(|field| !field.reserved()).map(|field| {
+        let cfgs = field.cfgs();
+        let (offset, bits) = (field.offset(), field.bits());
+        quote! {
+            #(#cfgs)*
+            (#ident::#offset, #ident::#bits),
+        }
+    }).collect::<TokenStream>();
+    let eq_masked = if let Some(repr) = &args.repr {
+        quote! {
+            const MASK: #repr = #repr::from_le_bytes(::bitfield::__private::field_mask(&[#defined]));
+            self.data & MASK == other.data & MASK
+        }
+    } else {
+        quote! {
+            const MASK: #storage = ::bitfield::__private::field_mask(&[#defined]);
+            let mut i = 0;
+            while i < MASK.len() && self.data[i] & MASK[i] == other.data[i] & MASK[i] {
+                i += 1;
+            }
+            i == MASK.len()
+        }
+    };
+
+    let write_bits = if let Some(repr) = &args.repr {
+        quote! {
+            let mut bytes = self.data.to_le_bytes();
+            ::bitfield::raw::try_store(&mut bytes, off, len, val)?;
+            self.data = #repr::from_le_bytes(bytes);
+        }
+    } else {
+        quote! { ::bitfield::raw::try_store(&mut self.data, off, len, val)?; }
+    };
+
+    // Everything else refers to the size through these, rather than
+    // repeating the sum over all fields.
+    let bytes_len = if args.repr.is_some() {
+        quote! { ::core::mem::size_of::<#storage>() }
+    } else {
+        quote! { ((Self::__BITS - 1) >> 3) + 1 }
+    };
+
+    let from_array = if let Some(repr) = &args.repr {
+        quote! { #repr::from_le_bytes(*bytes) }
+    } else {
+        quote! { *bytes }
+    };
+
+    let derives = (!args.derives.is_empty()).then(|| {
+        let derives = &args.derives;
+        quote! { #[derive(#(#derives),*)] }
+    });
+
     Ok(quote! {
+        #derives
         #(#attrs)*
-        #[repr(C)]
+        #repr
         #vis struct #ident {
-            data: [u8; (((#(<#field_tys as ::bitfield::Specifier>::BITS)+*) - 1) >> 3) + 1],
+            data: #storage,
         }
 
         impl #ident {
-            const OFFSET: [usize; #len] = [
-                #( 0 #(+ <#offsets as ::bitfield::Specifier>::BITS)*,)*
-            ];
+            #layout
+
+            #[doc(hidden)]
+            #vis const __BITS: usize = #total;
+            #[doc(hidden)]
+            #vis const __BYTES: usize = #bytes_len;
 
-            pub fn new() -> Self {
+            // Lists private fields too, so it stays inside the crate like
+            // their layout constants.
+            #[doc(hidden)]
+            pub(crate) const FIELDS: &'static [::bitfield::FieldInfo] = &[#(#infos),*];
+
+            #vis const fn new() -> Self {
                 Self {
-                    data: Default::default(),
+                    data: #zero,
                 }
             }
 
-            #(
-                pub fn #getters(&self) -> <#field_tys as ::bitfield::Specifier>::Type {
-                    let off = Self::OFFSET[#field_seq];
-                    <#field_tys as ::bitfield::Specifier>::get(off, &self.data[..])
-                }
-                pub fn #setters(&mut self, val: <#field_tys as ::bitfield::Specifier>::Type) {
-                    let off = Self::OFFSET[#field_seq];
-                    <#field_tys as ::bitfield::Specifier>::set(off, &mut self.data[..], val)
+            #vis const fn clear(&mut self) {
+                *self = Self::new();
+            }
+
+            #vis fn modify(&mut self, f: impl ::core::ops::FnOnce(&mut Self)) {
+                let mut staged = Self { data: self.data };
+                f(&mut staged);
+                self.data = staged.data;
+            }
+
+            #[track_caller]
+            #vis fn read_bits(&self, off: usize, len: usize) -> u64 {
+                self.try_read_bits(off, len).unwrap_or_else(|err| ::bitfield::__private::range_panic(err))
+            }
+
+            #[track_caller]
+            #vis fn write_bits(&mut self, off: usize, len: usize, val: u64) {
+                self.try_write_bits(off, len, val).unwrap_or_else(|err| ::bitfield::__private::range_panic(err))
+            }
+
+            #vis fn try_read_bits(&self, off: usize, len: usize) -> ::core::result::Result<u64, ::bitfield::RangeError> {
+                ::bitfield::raw::try_load(#bytes, off, len)
+            }
+
+            #vis fn try_write_bits(&mut self, off: usize, len: usize, val: u64) -> ::core::result::Result<(), ::bitfield::RangeError> {
+                #write_bits
+                ::core::result::Result::Ok(())
+            }
+
+            #vis fn from_slice(bytes: &[u8]) -> ::core::result::Result<Self, ::bitfield::LengthError> {
+                let bytes: &[u8; Self::__BYTES] = ::core::convert::TryInto::try_into(bytes)
+                    .map_err(|_| ::bitfield::LengthError {
+                        expected: Self::__BYTES,
+                        actual: bytes.len(),
+                    })?;
+                ::core::result::Result::Ok(Self { data: #from_array })
+            }
+
+            #vis fn eq_masked(&self, other: &Self) -> bool {
+                #eq_masked
+            }
+
+            #vis fn dump(&self) -> impl ::core::fmt::Display {
+                ::bitfield::__private::Dump {
+                    name: #name,
+                    fields: Self::FIELDS,
+                    bytes: #owned_bytes,
                 }
-            )*
+            }
+
+            #accessors
+        }
+
+        #widths
+
+        #declared
+
+        #conversions
+
+        #update
+
+        impl ::core::convert::TryFrom<&[u8]> for #ident {
+            type Error = ::bitfield::LengthError;
+
+            fn try_from(bytes: &[u8]) -> ::core::result::Result<Self, Self::Error> {
+                Self::from_slice(bytes)
+            }
+        }
+
+        impl ::core::fmt::Binary for #ident {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                ::bitfield::__private::fmt_binary(#bytes, f)
+            }
+        }
+
+        impl ::core::fmt::LowerHex for #ident {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                ::bitfield::__private::fmt_lower_hex(#bytes, f)
+            }
         }
 
-        impl ::bitfield::checks::TotalSizeModEight<{(0 #( + <#field_tys as ::bitfield::Specifier>::BITS )* ) % 8}> for #ident {}
+        impl ::bitfield::checks::TotalSizeModEight<{#ident::__BITS % 8}> for #ident {}
         impl ::bitfield::checks::TotalSizeIsMultipleOfEightBits for #ident {}
     })
 }
 
-fn gen(input: ItemStruct) -> syn::Result<TokenStream> {
+// `FooMasks::FIELD` is the field's mask in place and `FooMasks::field(val)`
+// shifts a raw value into it, for code that works on the `repr` directly.
+fn gen_masks(input: &ItemStruct, repr: &Ident, fields: &FieldsNamed) -> TokenStream {
+    let vis = &input.vis;
+    let ident = &input.ident;
+    let masks_ident = format_ident!("{}Masks", ident);
+
+    let items = fields.named.iter().map(FieldWrapper::from).map(|field| {
+        let fvis = field.vis();
+        let attrs = field.attrs();
+        let name = field.0.ident.as_ref().unwrap();
+        let mask = format_ident!("{}", name.unraw().to_string().to_uppercase());
+        let (offset, bits) = (field.offset(), field.bits());
+        quote! {
+            #(#attrs)*
+            #fvis const #mask: #repr = (#repr::MAX >> (#repr::BITS - #ident::#bits as u32)) << #ident::#offset;
+
+            #(#attrs)*
+            #fvis const fn #name(val: #repr) -> #repr {
+                val << #ident::#offset & Self::#mask
+            }
+        }
+    });
+
+    quote! {
+        #vis struct #masks_ident;
+
+        impl #masks_ident {
+            #(#items)*
+        }
+    }
+}
+
+fn gen_atomic(input: &ItemStruct, repr: &Ident, fields: &FieldsNamed) -> TokenStream {
+    let vis = &input.vis;
+    let ident = &input.ident;
+    let atomic_ident = format_ident!("Atomic{}", ident);
+    let atomic_ty = format_ident!("Atomic{}", repr.to_string().to_uppercase());
+
+    let accessors = fields.named.iter().map(FieldWrapper::from).map(|field| {
+        let ty = field.ty();
+        let fvis = field.vis();
+        let attrs = field.attrs();
+        let getter = field.getter();
+        let setter = field.setter();
+        let name = field.0.ident.as_ref().unwrap();
+        let load = format_ident!("load_{}", name);
+        let fetch_update = format_ident!("fetch_update_{}", name);
+        quote! {
+            #(#attrs)*
+            #fvis fn #load(&self, order: ::core::sync::atomic::Ordering) -> <#ty as ::bitfield::Specifier>::Type {
+                self.load(order).#getter()
+            }
+
+            #(#attrs)*
+            #fvis fn #fetch_update<F>(
+                &self,
+                set_order: ::core::sync::atomic::Ordering,
+                fetch_order: ::core::sync::atomic::Ordering,
+                mut f: F,
+            ) -> ::core::result::Result<<#ty as ::bitfield::Specifier>::Type, <#ty as ::bitfield::Specifier>::Type>
+            where
+                F: ::core::ops::FnMut(<#ty as ::bitfield::Specifier>::Type) -> ::core::option::Option<<#ty as ::bitfield::Specifier>::Type>,
+            {
+                self.data.fetch_update(set_order, fetch_order, |data| {
+                    let mut val = #ident { data };
+                    let new = f(val.#getter())?;
+                    val.#setter(new);
+                    ::core::option::Option::Some(val.data)
+                })
+                .map(|data| #ident { data }.#getter())
+                .map_err(|data| #ident { data }.#getter())
+            }
+        }
+    });
+
+    quote! {
+        #[repr(transparent)]
+        #vis struct #atomic_ident {
+            data: ::core::sync::atomic::#atomic_ty,
+        }
+
+        impl #atomic_ident {
+            #vis const fn new(val: #ident) -> Self {
+                Self {
+                    data: ::core::sync::atomic::#atomic_ty::new(val.data),
+                }
+            }
+
+            #vis fn into_inner(self) -> #ident {
+                #ident { data: self.data.into_inner() }
+            }
+
+            #vis fn load(&self, order: ::core::sync::atomic::Ordering) -> #ident {
+                #ident { data: self.data.load(order) }
+            }
+
+            #vis fn store(&self, val: #ident, order: ::core::sync::atomic::Ordering) {
+                self.data.store(val.data, order)
+            }
+
+            #vis fn fetch_update<F>(
+                &self,
+                set_order: ::core::sync::atomic::Ordering,
+                fetch_order: ::core::sync::atomic::Ordering,
+                mut f: F,
+            ) -> ::core::result::Result<#ident, #ident>
+            where
+                F: ::core::ops::FnMut(#ident) -> ::core::option::Option<#ident>,
+            {
+                self.data.fetch_update(set_order, fetch_order, |data| f(#ident { data }).map(|val| val.data))
+                    .map(|data| #ident { data })
+                    .map_err(|data| #ident { data })
+            }
+
+            #(#accessors)*
+        }
+    }
+}
+
+fn gen_diff(input: &ItemStruct, args: &Args, fields: &FieldsNamed) -> TokenStream {
+    let vis = &input.vis;
+    let ident = &input.ident;
+    let diff_ident = format_ident!("{}Diff", ident);
+
+    let fields = fields.named.iter().map(FieldWrapper::from).collect::<Vec<_>>();
+    let names = fields.iter().map(|f| f.0.ident.as_ref().unwrap()).collect::<Vec<_>>();
+    let cfgs = fields.iter().map(FieldWrapper::cfgs).collect::<Vec<_>>();
+    let fvis = fields.iter().map(FieldWrapper::vis);
+    let tys = fields.iter().map(FieldWrapper::ty);
+    let changes = fields.iter().map(|field| {
+        let getter = field.getter();
+        let (old, new) = (field.load(ident, quote! { &old }), field.load(ident, quote! { &new }));
+        quote! {
+            if #old != #new {
+                ::core::option::Option::Some((self.#getter(), other.#getter()))
+            } else {
+                ::core::option::Option::None
+            }
+        }
+    });
+
+    let bytes = if args.repr.is_some() {
+        quote! { (self.data.to_le_bytes(), other.data.to_le_bytes()) }
+    } else {
+        quote! { (self.data, other.data) }
+    };
+
+    quote! {
+        #vis struct #diff_ident {
+            #(#(#cfgs)* #fvis #names: ::core::option::Option<(<#tys as ::bitfield::Specifier>::Type, <#tys as ::bitfield::Specifier>::Type)>,)*
+        }
+
+        impl #diff_ident {
+            #vis fn is_empty(&self) -> bool {
+                #(#(#cfgs)* {
+                    if self.#names.is_some() {
+                        return false;
+                    }
+                })*
+                true
+            }
+        }
+
+        impl #ident {
+            #vis fn diff(&self, other: &Self) -> #diff_ident {
+                let (old, new) = #bytes;
+                #diff_ident {
+                    #(#(#cfgs)* #names: #changes,)*
+                }
+            }
+        }
+    }
+}
+
+fn gen_unpacked(input: &ItemStruct, fields: &FieldsNamed) -> TokenStream {
+    let vis = &input.vis;
+    let ident = &input.ident;
+    let fields_ident = format_ident!("{}Fields", ident);
+
+    let fields = fields.named.iter().map(FieldWrapper::from).collect::<Vec<_>>();
+    let names = fields.iter().map(|f| f.0.ident.as_ref().unwrap()).collect::<Vec<_>>();
+    let cfgs = fields.iter().map(FieldWrapper::cfgs).collect::<Vec<_>>();
+    let fvis = fields.iter().map(FieldWrapper::vis);
+    let tys = fields.iter().map(FieldWrapper::ty);
+    let getters = fields.iter().map(FieldWrapper::getter);
+    let setters = fields.iter().map(FieldWrapper::setter);
+
+    quote! {
+        #vis struct #fields_ident {
+            #(#(#cfgs)* #fvis #names: <#tys as ::bitfield::Specifier>::Type,)*
+        }
+
+        impl #ident {
+            #vis fn pack(fields: #fields_ident) -> Self {
+                let mut bitfield = Self::new();
+                #(#(#cfgs)* bitfield.#setters(fields.#names);)*
+                bitfield
+            }
+
+            #vis fn unpack(&self) -> #fields_ident {
+                #fields_ident {
+                    #(#(#cfgs)* #names: self.#getters(),)*
+                }
+            }
+        }
+    }
+}
+
+fn gen_view(input: &ItemStruct, fields: &FieldsNamed) -> TokenStream {
+    let vis = &input.vis;
+    let ident = &input.ident;
+    let view_ident = format_ident!("{}View", ident);
+    let view_mut_ident = format_ident!("{}ViewMut", ident);
+
+    let fields = fields.named.iter().map(FieldWrapper::from).collect::<Vec<_>>();
+    let getters = fields.iter().map(|field| {
+        let ty = field.ty();
+        let fvis = field.vis();
+        let attrs = field.attrs();
+        let constness = field.constness();
+        let getter = field.getter();
+        let get = field.decode(field.load(ident, quote! { self.0 }));
+        quote! {
+            #(#attrs)*
+            #fvis #constness fn #getter(&self) -> <#ty as ::bitfield::Specifier>::Type {
+                #get
+            }
+        }
+    }).collect::<TokenStream>();
+    let setters = fields.iter().map(|field| {
+        let ty = field.ty();
+        let fvis = field.vis();
+        let attrs = field.attrs();
+        let constness = field.constness();
+        let setter = field.setter();
+        let store = field.store(ident, quote! { self.0 }, field.encode(quote! { val }));
+        quote! {
+            #(#attrs)*
+            #fvis #constness fn #setter(&mut self, val: <#ty as ::bitfield::Specifier>::Type) {
+                #store
+            }
+        }
+    });
+
+    quote! {
+        #[derive(Clone, Copy)]
+        #vis struct #view_ident<'a>(&'a [u8]);
+
+        impl<'a> #view_ident<'a> {
+            #vis const fn new(data: &'a [u8]) -> ::core::option::Option<Self> {
+                if data.len() < ::core::mem::size_of::<#ident>() {
+                    return ::core::option::Option::None;
+                }
+                ::core::option::Option::Some(Self(data))
+            }
+
+            #getters
+        }
+
+        #vis struct #view_mut_ident<'a>(&'a mut [u8]);
+
+        impl<'a> #view_mut_ident<'a> {
+            #vis const fn new(data: &'a mut [u8]) -> ::core::option::Option<Self> {
+                if data.len() < ::core::mem::size_of::<#ident>() {
+                    return ::core::option::Option::None;
+                }
+                ::core::option::Option::Some(Self(data))
+            }
+
+            #vis fn as_view(&self) -> #view_ident<'_> {
+                #view_ident(self.0)
+            }
+
+            #getters
+
+            #(#setters)*
+        }
+    }
+}
+
+// `#[bitfield(serde = "bytes")]` (de)serializes the storage as it is,
+// `serde = "fields"` a map of the decoded field values, each checked to fit
+// its field.
+fn gen_serde(input: &ItemStruct, serde: &LitStr, args: &Args, fields: &FieldsNamed) -> TokenStream {
+    let ident = &input.ident;
+    let ident_str = ident.to_string();
+
+    if serde.value() != "fields" {
+        let (serialize, deserialize) = if let Some(repr) = &args.repr {
+            (
+                quote! { ::bitfield::__private::serde::Serialize::serialize(&self.data, serializer) },
+                quote! { <#repr as ::bitfield::__private::serde::Deserialize>::deserialize(deserializer) },
+            )
+        } else {
+            (
+                quote! { ::bitfield::__private::serialize_bytes(&self.data, serializer) },
+                quote! { ::bitfield::__private::deserialize_bytes(deserializer) },
+            )
+        };
+
+        return quote! {
+            impl ::bitfield::__private::serde::Serialize for #ident {
+                fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                where
+                    S: ::bitfield::__private::serde::Serializer,
+                {
+                    #serialize
+                }
+            }
+
+            impl<'de> ::bitfield::__private::serde::Deserialize<'de> for #ident {
+                fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                where
+                    D: ::bitfield::__private::serde::Deserializer<'de>,
+                {
+                    #deserialize.map(|data| Self { data })
+                }
+            }
+        };
+    }
+
+    let fields = fields.named.iter().map(FieldWrapper::from).collect::<Vec<_>>();
+    let names = fields.iter().map(|f| f.0.ident.as_ref().unwrap()).collect::<Vec<_>>();
+    let name_strs = names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+    let tys = fields.iter().map(FieldWrapper::ty).collect::<Vec<_>>();
+    let getters = fields.iter().map(FieldWrapper::getter).collect::<Vec<_>>();
+    let setters = fields.iter().map(FieldWrapper::setter).collect::<Vec<_>>();
+    let cfgs = fields.iter().map(FieldWrapper::cfgs).collect::<Vec<_>>();
+
+    quote! {
+        const _: () = {
+            #[derive(::bitfield::__private::serde::Deserialize)]
+            #[serde(crate = "::bitfield::__private::serde", rename = #ident_str, deny_unknown_fields)]
+            struct Fields {
+                #(#(#cfgs)* #names: <#tys as ::bitfield::Specifier>::Type,)*
+            }
+
+            impl ::bitfield::__private::serde::Serialize for #ident {
+                fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                where
+                    S: ::bitfield::__private::serde::Serializer,
+                {
+                    use ::bitfield::__private::serde::ser::SerializeStruct;
+
+                    let mut len = 0;
+                    #(#(#cfgs)* { len += 1; })*
+                    let mut state = serializer.serialize_struct(#ident_str, len)?;
+                    #(#(#cfgs)* state.serialize_field(#name_strs, &self.#getters())?;)*
+                    state.end()
+                }
+            }
+
+            impl<'de> ::bitfield::__private::serde::Deserialize<'de> for #ident {
+                fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                where
+                    D: ::bitfield::__private::serde::Deserializer<'de>,
+                {
+                    use ::bitfield::__private::serde::de::Error;
+
+                    let fields = <Fields as ::bitfield::__private::serde::Deserialize>::deserialize(deserializer)?;
+                    let mut bitfield = Self::new();
+                    #(
+                        #(#cfgs)*
+                        {
+                            let bits = <#tys as ::bitfield::Specifier>::BITS;
+                            let val = match <#tys as ::bitfield::Specifier>::checked_to(fields.#names) {
+                                ::core::option::Option::Some(raw) => <#tys as ::bitfield::Specifier>::checked_from(raw).ok_or_else(|| {
+                                    D::Error::custom(::core::format_args!("invalid value {:#x} for field `{}` of {} bits", raw, #name_strs, bits))
+                                }),
+                                ::core::option::Option::None => ::core::result::Result::Err(D::Error::custom(::core::format_args!(
+                                    "value out of range for field `{}` of {} bits",
+                                    #name_strs,
+                                    bits,
+                                ))),
+                            }?;
+                            bitfield.#setters(val);
+                        }
+                    )*
+                    ::core::result::Result::Ok(bitfield)
+                }
+            }
+        };
+    }
+}
+
+fn gen_arbitrary(input: &ItemStruct, fields: &FieldsNamed) -> TokenStream {
+    let ident = &input.ident;
+    let fields = fields.named.iter().map(FieldWrapper::from).collect::<Vec<_>>();
+    let cfgs = fields.iter().map(FieldWrapper::cfgs);
+    let tys = fields.iter().map(FieldWrapper::ty);
+    let setters = fields.iter().map(FieldWrapper::setter);
+
+    quote! {
+        impl<'a> ::bitfield::__private::arbitrary::Arbitrary<'a> for #ident {
+            fn arbitrary(u: &mut ::bitfield::__private::arbitrary::Unstructured<'a>) -> ::bitfield::__private::arbitrary::Result<Self> {
+                let mut bitfield = Self::new();
+                #(#(#cfgs)* bitfield.#setters(<#tys as ::bitfield::ArbitrarySpecifier>::arbitrary(u)?);)*
+                ::core::result::Result::Ok(bitfield)
+            }
+        }
+    }
+}
+
+fn gen_defmt(input: &ItemStruct, fields: &FieldsNamed) -> TokenStream {
+    let ident = &input.ident;
+    let fields = fields.named.iter().map(FieldWrapper::from).collect::<Vec<_>>();
+    let getters = fields.iter().map(FieldWrapper::getter);
+
+    // Which fields exist depends on `#[cfg]`, so they can't share one format
+    // string.
+    if fields.iter().any(|f| !f.cfgs().is_empty()) {
+        let cfgs = fields.iter().map(FieldWrapper::cfgs);
+        let formats = fields.iter().map(|f| format!("{{=str}}{}: {{}}", f.0.ident.as_ref().unwrap()));
+        let head = format!("{} {{{{ ", ident);
+        return quote! {
+            impl ::bitfield::__private::defmt::Format for #ident {
+                #[allow(unused_assignments)]
+                fn format(&self, f: ::bitfield::__private::defmt::Formatter) {
+                    let mut sep = "";
+                    ::bitfield::__private::defmt::write!(f, #head);
+                    #(#(#cfgs)* {
+                        ::bitfield::__private::defmt::write!(f, #formats, sep, self.#getters());
+                        sep = ", ";
+                    })*
+                    ::bitfield::__private::defmt::write!(f, " }}");
+                }
+            }
+        };
+    }
+
+    let format = format!(
+        "{} {{{{ {} }}}}",
+        ident,
+        fields.iter().map(|f| format!("{}: {{}}", f.0.ident.as_ref().unwrap())).collect::<Vec<_>>().join(", "),
+    );
+
+    quote! {
+        impl ::bitfield::__private::defmt::Format for #ident {
+            fn format(&self, f: ::bitfield::__private::defmt::Formatter) {
+                ::bitfield::__private::defmt::write!(f, #format #(, self.#getters())*)
+            }
+        }
+    }
+}
+
+fn gen(input: ItemStruct, args: Args) -> syn::Result<TokenStream> {
     match &input.fields {
-        Fields::Named(fields) => gen_standard(&input, fields),
-        _ => todo!(),
+        Fields::Named(fields) => {
+            let mut tokens = gen_standard(&input, &args, fields)?;
+            if let (Some(repr), Some(..)) = (&args.repr, &args.atomic) {
+                tokens.extend(gen_atomic(&input, repr, fields));
+            }
+            if let (Some(repr), Some(..)) = (&args.repr, &args.masks) {
+                tokens.extend(gen_masks(&input, repr, fields));
+            }
+            if args.unpacked.is_some() {
+                tokens.extend(gen_unpacked(&input, fields));
+            }
+            if args.diff.is_some() {
+                tokens.extend(gen_diff(&input, &args, fields));
+            }
+            if args.view.is_some() {
+                tokens.extend(gen_view(&input, fields));
+            }
+            if let Some(arbitrary) = &args.arbitrary {
+                if !cfg!(feature = "arbitrary") {
+                    return Err(syn::Error::new_spanned(arbitrary, "`arbitrary` requires the `arbitrary` feature of `bitfield`"));
+                }
+                tokens.extend(gen_arbitrary(&input, fields));
+            }
+            if let Some(defmt) = &args.defmt {
+                if !cfg!(feature = "defmt") {
+                    return Err(syn::Error::new_spanned(defmt, "`defmt` requires the `defmt` feature of `bitfield`"));
+                }
+                tokens.extend(gen_defmt(&input, fields));
+            }
+            if let Some(serde) = &args.serde {
+                if !cfg!(feature = "serde") {
+                    return Err(syn::Error::new_spanned(serde, "`serde` requires the `serde` feature of `bitfield`"));
+                }
+                tokens.extend(gen_serde(&input, serde, &args, fields));
+            }
+            Ok(tokens)
+        }
+        Fields::Unit => Err(syn::Error::new_spanned(&input.ident, "#[bitfield] requires at least one field")),
+        Fields::Unnamed(fields) => Err(syn::Error::new_spanned(fields, "#[bitfield] requires named fields")),
     }
 }
 
@@ -93,8 +1310,46 @@ pub fn bitfield(args: TokenStream, input: TokenStream) -> TokenStream {
         return syn::Error::new_spanned(args, "expect struct").to_compile_error();
     };
 
-    match gen(item) {
-        Ok(tokens) => tokens,
+    match syn::parse2::<Args>(args) {
+        Ok(args) => expand(item, args),
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+// `bitfield! { #[bitfield(...)] struct Foo { ... } }`, for generating layouts
+// from `macro_rules!` or `seq!` bodies. The options, if any, are given by
+// `#[bitfield(...)]` attributes on the struct itself.
+pub fn bitfield_item(input: TokenStream) -> TokenStream {
+    let mut item = match syn::parse2::<ItemStruct>(input) {
+        Ok(item) => item,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    let (own, attrs) = item.attrs.into_iter().partition::<Vec<_>, _>(|attr| attr.path.is_ident("bitfield"));
+    item.attrs = attrs;
+    let mut args = TokenStream::new();
+    for attr in own.iter().filter(|attr| !attr.tokens.is_empty()) {
+        match attr.parse_args::<TokenStream>() {
+            Ok(tokens) => args.extend(quote! { #tokens, }),
+            Err(err) => return err.to_compile_error(),
+        }
+    }
+
+    match syn::parse2::<Args>(args) {
+        Ok(args) => expand(item, args),
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+fn expand(item: ItemStruct, args: Args) -> TokenStream {
+    let debug_expand = args.debug_expand.is_some();
+    match gen(item, args) {
+        Ok(tokens) => {
+            if debug_expand {
+                attrs::debug_expand(&tokens);
+            }
+            tokens
+        }
         Err(err) => err.to_compile_error(),
     }
 }
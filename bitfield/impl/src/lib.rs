@@ -8,7 +8,12 @@ pub fn bitfield(args: TokenStream, input: TokenStream) -> TokenStream {
     imp::bitfield(args.into(), input.into()).into()
 }
 
-#[proc_macro_derive(BitfieldSpecifier)]
+#[proc_macro]
+pub fn bitfield_item(input: TokenStream) -> TokenStream {
+    imp::bitfield_item(input.into()).into()
+}
+
+#[proc_macro_derive(BitfieldSpecifier, attributes(bits))]
 pub fn derive(input: TokenStream) -> TokenStream {
     derive::derive(input.into()).into()
 }
@@ -10,294 +10,517 @@
 //
 // From the perspective of a user of this crate, they get all the necessary APIs
 // (macro, trait, struct) through the one bitfield crate.
+#![no_std]
+
 pub use bitfield_impl::{bitfield, BitfieldSpecifier};
 
-use std::marker::PhantomData;
-use std::ops::RangeInclusive;
-use std::convert::{TryInto, TryFrom};
+/// `bitfield! { #[bitfield(...)] struct Foo { ... } }`, the function-like
+/// form of `#[bitfield]` for use inside `macro_rules!` and `seq!` bodies.
+///
+/// It lives in its own module because an attribute and a function-like macro
+/// cannot share a name in one module.
+pub mod macros {
+    pub use bitfield_impl::bitfield_item as bitfield;
+}
+
+use core::marker::PhantomData;
+use core::convert::{TryInto, TryFrom};
 
 pub mod checks {
     pub trait TotalSizeModEight<const N: usize> {}
     pub trait TotalSizeIsMultipleOfEightBits: TotalSizeModEight<0> {}
+
+    pub enum True {}
+    pub enum False {}
+
+    pub struct Check<const B: bool>;
+
+    pub trait Select {
+        type Type;
+    }
+
+    impl Select for Check<true> {
+        type Type = True;
+    }
+
+    impl Select for Check<false> {
+        type Type = False;
+    }
+
+    pub trait AllBitPatternsCovered {}
+    impl AllBitPatternsCovered for True {}
 }
 
-mod private {
-    use super::*;
+/// Bit-level access to packed storage.
+///
+/// Bits are numbered LSB-first within each byte and bytes are little-endian,
+/// which is the layout `#[bitfield]` uses. Custom [`Specifier`]
+/// implementations can use these to read or write their bits directly, e.g.
+/// from `Specifier::get`/`Specifier::set`.
+///
+/// `try_load`/`try_store` check the range and return a [`RangeError`] when it
+/// doesn't fit, and `load`/`store` panic instead. `load_unchecked` and
+/// `store_unchecked` are for ranges known to fit, like the fields of a
+/// `#[bitfield]` struct, and only check them with `debug_assert!`.
+pub mod raw {
+    use super::RangeError;
+
+    const fn mask(len: usize) -> u64 {
+        if len == 0 { 0 } else { u64::MAX >> (64 - len) }
+    }
+
+    // Whether `len` bits starting at bit `off` lie within `data`.
+    const fn within(data: &[u8], off: usize, len: usize) -> bool {
+        match off.checked_add(len) {
+            Some(end) => end <= data.len() * 8,
+            None => false,
+        }
+    }
 
-    pub trait Num {
-        const BITS_RANGE: RangeInclusive<u32>;
+    const fn check(data: &[u8], off: usize, len: usize) -> Result<(), RangeError> {
+        if len <= 64 && within(data, off, len) {
+            Ok(())
+        } else {
+            Err(RangeError { offset: off, len, size: data.len() * 8 })
+        }
+    }
 
-        fn view(off: usize, len: usize, data: &[u8]) -> &[u8] {
-            if !Self::BITS_RANGE.contains(&(len as u32)) {
-                panic!();
-            }
+    /// Reads `len` bits (at most 64) starting at bit `off` of `data`.
+    pub const fn try_load(data: &[u8], off: usize, len: usize) -> Result<u64, RangeError> {
+        match check(data, off, len) {
+            Ok(()) => Ok(load_unchecked(data, off, len)),
+            Err(err) => Err(err),
+        }
+    }
 
-            let begin = off >> 3;
-            let end = (off + len - 1) >> 3; // inclusive
-            if data.is_empty() || end + 1 > data.len() {
-                panic!();
+    /// Writes the low `len` bits (at most 64) of `val` starting at bit `off` of
+    /// `data`, leaving the surrounding bits untouched.
+    pub const fn try_store(data: &mut [u8], off: usize, len: usize, val: u64) -> Result<(), RangeError> {
+        match check(data, off, len) {
+            Ok(()) => {
+                store_unchecked(data, off, len, val);
+                Ok(())
             }
+            Err(err) => Err(err),
+        }
+    }
 
-            &data[begin..=end]
+    /// Like [`try_load`], but panics if the range does not fit in `data`.
+    #[track_caller]
+    pub const fn load(data: &[u8], off: usize, len: usize) -> u64 {
+        match try_load(data, off, len) {
+            Ok(v) => v,
+            Err(_) => panic!("bit range wider than 64 bits or past the end of the data"),
         }
+    }
 
-        fn view_mut(off: usize, len: usize, data: &mut [u8]) -> &mut [u8] {
-            if !Self::BITS_RANGE.contains(&(len as u32)) {
-                panic!();
+    /// Like [`try_store`], but panics if the range does not fit in `data`.
+    #[track_caller]
+    pub const fn store(data: &mut [u8], off: usize, len: usize, val: u64) {
+        if try_store(data, off, len, val).is_err() {
+            panic!("bit range wider than 64 bits or past the end of the data")
+        }
+    }
+
+    /// Reads `len` bits starting at bit `off` of `data` without checking the
+    /// range, copying whole bytes when it starts and ends on byte boundaries.
+    ///
+    /// The range must be at most 64 bits wide and lie within `data`, which is
+    /// only checked by `debug_assert!`. Otherwise the result is unspecified or
+    /// indexing `data` panics; it is never undefined behavior.
+    #[inline]
+    pub const fn load_unchecked(data: &[u8], off: usize, len: usize) -> u64 {
+        debug_assert!(check(data, off, len).is_ok(), "bit range wider than 64 bits or past the end of the data");
+        if off & 7 == 0 && len & 7 == 0 {
+            return load_bytes_unchecked(data, off >> 3, len >> 3);
+        }
+        let begin = off >> 3;
+        let shift = off & 7;
+
+        // Read the whole 64-bit word containing the range when the buffer has
+        // one, otherwise assemble the bytes one by one.
+        if shift + len <= 64 {
+            if let Some((_, rest)) = data.split_at_checked(begin) {
+                if let Some(word) = rest.first_chunk::<8>() {
+                    return u64::from_le_bytes(*word) >> shift & mask(len);
+                }
             }
+        }
+
+        let end = (off + len + 7) >> 3;
+        let mut buf = 0u128;
+        let mut i = begin;
+        while i < end {
+            buf |= (data[i] as u128) << ((i - begin) << 3);
+            i += 1;
+        }
+        (buf >> shift) as u64 & mask(len)
+    }
 
-            let begin = off >> 3;
-            let end = (off + len - 1) >> 3; // inclusive
-            if data.is_empty() || end + 1 > data.len() {
-                panic!();
+    /// Writes the low `len` bits of `val` starting at bit `off` of `data`
+    /// without checking the range, with the same requirements as
+    /// [`load_unchecked`].
+    #[inline]
+    pub const fn store_unchecked(data: &mut [u8], off: usize, len: usize, val: u64) {
+        debug_assert!(check(data, off, len).is_ok(), "bit range wider than 64 bits or past the end of the data");
+        if off & 7 == 0 && len & 7 == 0 {
+            return store_bytes_unchecked(data, off >> 3, len >> 3, val);
+        }
+        let begin = off >> 3;
+        let shift = off & 7;
+
+        if shift + len <= 64 {
+            if let Some((_, rest)) = data.split_at_mut_checked(begin) {
+                if let Some(word) = rest.first_chunk_mut::<8>() {
+                    let m = mask(len) << shift;
+                    let w = u64::from_le_bytes(*word);
+                    *word = (w & !m | val << shift & m).to_le_bytes();
+                    return;
+                }
             }
+        }
+
+        let end = (off + len + 7) >> 3;
+        let m = (mask(len) as u128) << shift;
+        let val = ((val as u128) << shift) & m;
 
-            &mut data[begin..=end]
+        let mut i = begin;
+        while i < end {
+            let shift = (i - begin) << 3;
+            data[i] = data[i] & !((m >> shift) as u8) | (val >> shift) as u8;
+            i += 1;
         }
     }
 
-    pub trait Load: Num {
-        fn load(off: usize, len: usize, data: &[u8]) -> Self;
+    /// Reads `len` whole bytes (at most 8) starting at byte `begin` of `data`
+    /// as a little-endian integer.
+    ///
+    /// Panics if the range does not fit in `data`.
+    #[track_caller]
+    pub const fn load_bytes(data: &[u8], begin: usize, len: usize) -> u64 {
+        if len > 8 || !within(data, begin.saturating_mul(8), len * 8) {
+            panic!("byte range wider than 8 bytes or past the end of the data")
+        }
+        load_bytes_unchecked(data, begin, len)
     }
 
-    pub trait Store: Num {
-        fn store(off: usize, len: usize, data: &mut [u8], val: Self);
+    /// Writes the low `len` bytes (at most 8) of `val` in little-endian order
+    /// starting at byte `begin` of `data`.
+    ///
+    /// Panics if the range does not fit in `data`.
+    #[track_caller]
+    pub const fn store_bytes(data: &mut [u8], begin: usize, len: usize, val: u64) {
+        if len > 8 || !within(data, begin.saturating_mul(8), len * 8) {
+            panic!("byte range wider than 8 bytes or past the end of the data")
+        }
+        store_bytes_unchecked(data, begin, len, val)
     }
-}
 
-fn split(data: &[u8]) -> (u8, &[u8], u8) {
-    if data.len() < 2 {
-        panic!();
+    const fn load_bytes_unchecked(data: &[u8], begin: usize, len: usize) -> u64 {
+        let mut buf = [0u8; 8];
+        let mut i = 0;
+        while i < len {
+            buf[i] = data[begin + i];
+            i += 1;
+        }
+        u64::from_le_bytes(buf)
     }
 
-    let h = data[0];
-    let t = data[data.len() - 1];
-    (h, &data[1..data.len() - 1], t)
-}
+    const fn store_bytes_unchecked(data: &mut [u8], begin: usize, len: usize, val: u64) {
+        let buf = val.to_le_bytes();
+        let mut i = 0;
+        while i < len {
+            data[begin + i] = buf[i];
+            i += 1;
+        }
+    }
 
-impl private::Num for u8 {
-    const BITS_RANGE: RangeInclusive<u32> = 1 ..= Self::BITS;
-}
+    /// Reads `M` bytes starting at bit `off` of `data`; the range can be wider
+    /// than 64 bits.
+    ///
+    /// Panics if the range does not fit in `data`.
+    #[track_caller]
+    pub const fn load_array<const M: usize>(data: &[u8], off: usize) -> [u8; M] {
+        if !within(data, off, M * 8) {
+            panic!("bit range past the end of the data")
+        }
+        let mut out = [0u8; M];
+        let mut i = 0;
+        while i < M {
+            out[i] = load_unchecked(data, off + (i << 3), 8) as u8;
+            i += 1;
+        }
+        out
+    }
 
-impl private::Num for u16 {
-    const BITS_RANGE: RangeInclusive<u32> = u8::BITS + 1 ..= Self::BITS;
+    /// Writes `val` starting at bit `off` of `data`, leaving the surrounding
+    /// bits untouched.
+    ///
+    /// Panics if the range does not fit in `data`.
+    #[track_caller]
+    pub const fn store_array<const M: usize>(data: &mut [u8], off: usize, val: &[u8; M]) {
+        if !within(data, off, M * 8) {
+            panic!("bit range past the end of the data")
+        }
+        let mut i = 0;
+        while i < M {
+            store_unchecked(data, off + (i << 3), 8, val[i] as u64);
+            i += 1;
+        }
+    }
 }
 
-impl private::Num for u32 {
-    const BITS_RANGE: RangeInclusive<u32> = u16::BITS + 1 ..= Self::BITS;
-}
+#[doc(hidden)]
+pub mod __private {
+    use core::fmt;
 
-impl private::Num for u64 {
-    const BITS_RANGE: RangeInclusive<u32> = u32::BITS + 1 ..= Self::BITS;
-}
+    pub fn fmt_binary(data: &[u8], f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0b")?;
+        }
+        data.iter().rev().try_for_each(|b| write!(f, "{:08b}", b))
+    }
 
-impl private::Load for u8 {
-    fn load(off: usize, len: usize, data: &[u8]) -> u8 {
-        let data = <Self as private::Num>::view(off, len, data);
-        let mask = Self::MAX >> (Self::BITS - len as u32);
-        let off = off as u32 % u8::BITS;
+    pub fn fmt_lower_hex(data: &[u8], f: &mut fmt::Formatter) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        data.iter().rev().try_for_each(|b| write!(f, "{:02x}", b))
+    }
 
-        match data {
-            &[b] => u8::from_le_bytes([b]) >> off & mask,
-            &[b, o] => (u16::from_le_bytes([b, o]) >> off) as u8 & mask,
-            _ => unreachable!(),
+    // The bits of every `(offset, bits)` field set. Fields that don't fit are
+    // skipped here and reported by their own checks.
+    pub const fn field_mask<const N: usize>(fields: &[(usize, usize)]) -> [u8; N] {
+        let mut mask = [0; N];
+        let mut i = 0;
+        while i < fields.len() {
+            let (mut off, mut bits) = fields[i];
+            if off.saturating_add(bits) <= N * 8 {
+                while bits > 0 {
+                    let len = if bits > 64 { 64 } else { bits };
+                    super::raw::store_unchecked(&mut mask, off, len, u64::MAX);
+                    off += len;
+                    bits -= len;
+                }
+            }
+            i += 1;
         }
+        mask
+    }
+
+    #[cold]
+    #[track_caller]
+    pub fn range_panic(err: super::RangeError) -> ! {
+        panic!("{}", err)
     }
-}
 
-impl private::Load for u16 {
-    fn load(off: usize, len:usize, data: &[u8]) -> u16 {
-        let data = <Self as private::Num>::view(off, len, data);
-        let mask = Self::MAX >> (Self::BITS - len as u32);
-        let off = off as u32 % u8::BITS;
+    #[cold]
+    #[track_caller]
+    pub fn conversion_panic<I>(v: u64, bits: usize) -> ! {
+        panic!("{:#x} does not fit in `{}` for a {}-bit field", v, core::any::type_name::<I>(), bits)
+    }
 
-        match data {
-            &[b1, b2] => Self::from_le_bytes([b1, b2]) >> off & mask,
-            &[b1, b2, o] => (u32::from_le_bytes([b1, b2, o, 0]) >> off) as Self & mask,
-            z => unreachable!("{:?}", z),
+    // Where `layout = "c"` puts a field of `bits` bits declared with a
+    // `unit`-bit type after `end` bits are taken, following the SysV ABI:
+    // it moves to the next unit only if it would otherwise straddle one.
+    pub const fn c_place(end: usize, bits: usize, unit: usize) -> usize {
+        if bits != 0 && end / unit != (end + bits - 1) / unit {
+            (end / unit + 1) * unit
+        } else {
+            end
         }
     }
-}
 
-impl private::Load for u32 {
-    fn load(off: usize, len: usize, data: &[u8]) -> u32 {
-        let data = <Self as private::Num>::view(off, len, data);
-        let mask = Self::MAX >> (Self::BITS - len as u32);
-        let off = off as u32 % u8::BITS;
+    pub struct Dump<const N: usize> {
+        pub name: &'static str,
+        pub fields: &'static [super::FieldInfo],
+        pub bytes: [u8; N],
+    }
 
-        match data {
-            &[b1, b2, b3] => Self::from_le_bytes([b1, b2, b3, 0]) >> off & mask,
-            &[b1, b2, b3, b4] => Self::from_le_bytes([b1, b2, b3, b4]) >> off & mask,
-            &[b1, b2, b3, b4, o] => (u64::from_le_bytes([b1, b2, b3, b4, o, 0, 0, 0]) >> off) as Self & mask,
-            z => unreachable!("{:?}", z),
+    impl<const N: usize> fmt::Display for Dump<N> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            writeln!(f, "{} {{", self.name)?;
+            let width = self.fields.iter().map(|field| field.name.len()).max().unwrap_or(0);
+            for field in self.fields {
+                write!(f, "    {:width$} ", field.name, width = width)?;
+                if field.bits == 1 {
+                    write!(f, "[{}]", field.offset)?;
+                } else {
+                    write!(f, "[{}:{}]", field.offset + field.bits - 1, field.offset)?;
+                }
+                if field.bits > 64 {
+                    f.write_str(" = [")?;
+                    for i in 0..field.bits >> 3 {
+                        let byte = super::raw::load(&self.bytes, field.offset + (i << 3), 8);
+                        write!(f, "{}{:02x}", if i == 0 { "" } else { " " }, byte)?;
+                    }
+                    writeln!(f, "]")?;
+                    continue;
+                }
+                let val = super::raw::load(&self.bytes, field.offset, field.bits);
+                writeln!(f, " = {:#0bits$b} ({:#x})", val, val, bits = field.bits + 2)?;
+            }
+            write!(f, "}}")
         }
     }
-}
 
-impl private::Load for u64 {
-    fn load(off: usize, len: usize, data: &[u8]) -> u64 {
-        let data = <Self as private::Num>::view(off, len, data);
-        let mask = Self::MAX >> (Self::BITS - len as u32);
-        let off = off as u32 % u8::BITS;
+    #[cfg(feature = "arbitrary")]
+    pub use arbitrary;
 
-        match data {
-            &[b1, b2, b3, b4, b5] => Self::from_le_bytes([b1, b2, b3, b4, b5, 0, 0, 0]) >> off & mask,
-            &[b1, b2, b3, b4, b5, b6] => Self::from_le_bytes([b1, b2, b3, b4, b5, b6, 0, 0]) >> off & mask,
-            &[b1, b2, b3, b4, b5, b6, b7] => Self::from_le_bytes([b1, b2, b3, b4, b5, b6, b7, 0]) >> off & mask,
-            &[b1, b2, b3, b4, b5, b6, b7, b8] => Self::from_le_bytes([b1, b2, b3, b4, b5, b6, b7, b8]) >> off & mask,
-            &[b1, b2, b3, b4, b5, b6, b7, b8, o] => (u128::from_le_bytes([b1, b2, b3, b4, b5, b6, b7, b8, o, 0, 0, 0, 0, 0, 0, 0]) >> off) as Self & mask,
-            z => unreachable!("{:?}", z),
-        }
+    #[cfg(feature = "defmt")]
+    pub use defmt;
+
+    #[cfg(feature = "serde")]
+    pub use serde;
+
+    #[cfg(feature = "serde")]
+    pub fn serialize_bytes<S: serde::Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(data)
     }
-}
 
-impl private::Store for u8 {
-    fn store(off: usize, len:usize, data: &mut [u8], val: u8) {
-        let data = <Self as private::Num>::view_mut(off, len, data);
-        let mask = Self::MAX >> (Self::BITS - len as u32);
-        let val = val & mask;
-
-        match data {
-            &mut [b] => {
-                let off = off as u32 % u8::BITS;
-                let m = mask << off;
-                data[0] = b & !m | (val << off)
+    #[cfg(feature = "serde")]
+    pub fn deserialize_bytes<'de, D: serde::Deserializer<'de>, const N: usize>(deserializer: D) -> Result<[u8; N], D::Error> {
+        use core::convert::TryInto;
+        use serde::de::{Error, SeqAccess, Visitor};
+
+        struct BytesVisitor<const N: usize>;
+
+        impl<'de, const N: usize> Visitor<'de> for BytesVisitor<N> {
+            type Value = [u8; N];
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{} bytes", N)
             }
-            &mut [h, t] => {
-                let mh = u8::MAX << (off as u32 % u8::BITS);
-                let mt = u8::MAX << ((off + len) as u32 % u8::BITS);
-                let buf = ((val as u16) << (off as u32 % u8::BITS)).to_le_bytes();
-                let (head, _, tail) = split(&buf[..data.len()]);
-
-                data[0] = h & !mh | head;
-                data[data.len() - 1] = t & mt | tail;
+
+            fn visit_bytes<E: Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                v.try_into().map_err(|_| E::invalid_length(v.len(), &self))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut data = [0; N];
+                for (i, b) in data.iter_mut().enumerate() {
+                    *b = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(i, &self))?;
+                }
+                if seq.next_element::<u8>()?.is_some() {
+                    return Err(A::Error::invalid_length(N + 1, &self));
+                }
+                Ok(data)
             }
-            _ => unreachable!(),
         }
+
+        deserializer.deserialize_bytes(BytesVisitor)
     }
 }
 
-impl private::Store for u16 {
-    fn store(off: usize, len:usize, data: &mut [u8], val: u16) {
-        let data = <Self as private::Num>::view_mut(off, len, data);
-        let mask = Self::MAX >> (Self::BITS - len as u32);
-        let val = val & mask;
-
-        match data {
-            &mut [h, t] => {
-                let mh = u8::MAX << (off as u32 % u8::BITS);
-                let mt = u8::MAX << ((off + len) as u32 % u8::BITS);
-                let buf = ((val as Self) << (off as u32 % u8::BITS)).to_le_bytes();
-                let (head, _, tail) = split(&buf[..data.len()]);
-
-                data[0] = h & !mh | head;
-                data[data.len() - 1] = t & mt | tail;
-            }
-            &mut [h, _, t] => {
-                let mh = u8::MAX << (off as u32 % u8::BITS);
-                let mt = u8::MAX << ((off + len) as u32 % u8::BITS);
-                let buf = ((val as u32) << (off as u32 % u8::BITS)).to_le_bytes();
-                let (head, buf, tail) = split(&buf[..data.len()]);
-
-                let last = data.len() - 1;
-                data[0] = h & !mh | head;
-                data[1 .. last].copy_from_slice(buf);
-                data[last] = t & mt | tail;
-            }
-            _ => unreachable!(),
-        }
+mod private {
+    pub trait Load {
+        fn load(off: usize, len: usize, data: &[u8]) -> Self;
     }
+
+    pub trait Store {
+        fn store(off: usize, len: usize, data: &mut [u8], val: Self);
+    }
+
+    macro_rules! impl_load_store {
+        ($($ty:ty)*) => {
+            $(
+                impl Load for $ty {
+                    fn load(off: usize, len: usize, data: &[u8]) -> Self {
+                        super::raw::load(data, off, len) as Self
+                    }
+                }
+
+                impl Store for $ty {
+                    fn store(off: usize, len: usize, data: &mut [u8], val: Self) {
+                        super::raw::store(data, off, len, val as u64)
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_load_store!(u8 u16 u32 u64);
 }
 
-impl private::Store for u32 {
-    fn store(off: usize, len:usize, data: &mut [u8], val: u32) {
-        let data = <Self as private::Num>::view_mut(off, len, data);
-        let mask = Self::MAX >> (Self::BITS - len as u32);
-        let val = val & mask;
-
-        match data {
-            &mut [h, _, t] | &mut [h, _, _, t] => {
-                let mh = u8::MAX << (off as u32 % u8::BITS);
-                let mt = u8::MAX << ((off + len) as u32 % u8::BITS);
-                let buf = ((val as Self) << (off as u32 % u8::BITS)).to_le_bytes();
-                let (head, buf, tail) = split(&buf[..data.len()]);
-
-                let last = data.len() - 1;
-                data[0] = h & !mh | head;
-                data[1 .. last].copy_from_slice(buf);
-                data[last] = t & mt | tail;
-            }
-            &mut [h, _, _, _, t] => {
-                let mh = u8::MAX << (off as u32 % u8::BITS);
-                let mt = u8::MAX << ((off + len) as u32 % u8::BITS);
-                let buf = ((val as u64) << (off as u32 % u8::BITS)).to_le_bytes();
-                let (head, buf, tail) = split(&buf[..data.len()]);
-
-                let last = data.len() - 1;
-                data[0] = h & !mh | head;
-                data[1 .. last].copy_from_slice(buf);
-                data[last] = t & mt | tail;
-            }
-            _ => unreachable!(),
-        }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldInfo {
+    pub name: &'static str,
+    pub offset: usize,
+    pub bits: usize,
+}
+
+/// Returned by `from_slice` / `TryFrom<&[u8]>` when the slice length does not
+/// match the size of the bitfield.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LengthError {
+    pub expected: usize,
+    pub actual: usize,
+}
+
+impl core::fmt::Display for LengthError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "expected {} bytes, got {}", self.expected, self.actual)
     }
 }
 
-impl private::Store for u64 {
-    fn store(off: usize, len:usize, data: &mut [u8], val: u64) {
-        let data = <Self as private::Num>::view_mut(off, len, data);
-        let mask = Self::MAX >> (Self::BITS - len as u32);
-        let val = val & mask;
-
-        match data {
-            &mut [h, _, _, _, t] | &mut [h, _, _, _, _, t] | &mut [h, _, _, _, _, _, t] | &mut [h, _, _, _, _, _, _, t] => {
-                let mh = u8::MAX << (off as u32 % u8::BITS);
-                let mt = u8::MAX << ((off + len) as u32 % u8::BITS);
-                let buf = ((val as Self) << (off as u32 % u8::BITS)).to_le_bytes();
-                let (head, buf, tail) = split(&buf[..data.len()]);
-
-                let last = data.len() - 1;
-                data[0] = h & !mh | head;
-                data[1 .. last].copy_from_slice(buf);
-                data[last] = t & mt | tail;
-            }
-            &mut [h, _, _, _, _, _, _, _, t] => {
-                let mh = u8::MAX << (off as u32 % u8::BITS);
-                let mt = u8::MAX << ((off + len) as u32 % u8::BITS);
-                let buf = ((val as u128) << (off as u32 % u8::BITS)).to_le_bytes();
-                let (head, buf, tail) = split(&buf[..data.len()]);
-
-                let last = data.len() - 1;
-                data[0] = h & !mh | head;
-                data[1 .. last].copy_from_slice(buf);
-                data[last] = t & mt | tail;
-            }
-            _ => unreachable!(),
-        }
+impl core::error::Error for LengthError {}
+
+/// Returned by `try_read_bits` / `try_write_bits` and [`raw::try_load`] /
+/// [`raw::try_store`] for a bit range that is wider than 64 bits or extends
+/// past the end of the bitfield.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RangeError {
+    pub offset: usize,
+    pub len: usize,
+    pub size: usize,
+}
+
+impl core::fmt::Display for RangeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "bit range {}..{} out of bounds for a {}-bit bitfield (at most 64 bits at a time)",
+            self.offset,
+            self.offset.wrapping_add(self.len),
+            self.size,
+        )
     }
 }
 
+impl core::error::Error for RangeError {}
+
+/// A type that can be stored in a `#[bitfield]` field.
+///
+/// Implementors provide `BITS`, `Type` and the `from`/`to` conversions between
+/// `Type` and the raw bits; everything else has a default built on [`raw`].
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be used as a bitfield field type",
+    label = "not a bitfield specifier",
+    note = "use `B<N>`, `B1`..`B64`, `bool`, `char`, `f32`, `f64`, a `#[derive(BitfieldSpecifier)]` enum, or implement `Specifier`",
+)]
 pub trait Specifier {
     const BITS: usize;
     type Type;
 
+    // A specifier over 64 bits that doesn't override `get` fails to compile
+    // when `get` is used, rather than panicking.
     fn get(off: usize, data: &[u8]) -> Self::Type {
+        const { assert!(Self::BITS <= 64, "specifiers over 64 bits must override `Specifier::get`") };
         match Self::BITS {
             0..=8 => Self::from_u8(<u8 as private::Load>::load(off, Self::BITS, data)),
             9..=16 => Self::from_u16(<u16 as private::Load>::load(off, Self::BITS, data)),
             17..=32 => Self::from_u32(<u32 as private::Load>::load(off, Self::BITS, data)),
-            33..=64 => Self::from_u64(<u64 as private::Load>::load(off, Self::BITS, data)),
-            _ => unreachable!(),
+            _ => Self::from_u64(<u64 as private::Load>::load(off, Self::BITS, data)),
         }
     }
 
     fn set(off: usize, data: &mut [u8], val: Self::Type) {
+        const { assert!(Self::BITS <= 64, "specifiers over 64 bits must override `Specifier::set`") };
         match Self::BITS {
             0..=8 => <u8 as private::Store>::store(off, Self::BITS, data, Self::to_u8(val)),
             9..=16 => <u16 as private::Store>::store(off, Self::BITS, data, Self::to_u16(val)),
             17..=32 => <u32 as private::Store>::store(off, Self::BITS, data, Self::to_u32(val)),
-            33..=64 => <u64 as private::Store>::store(off, Self::BITS, data, Self::to_u64(val)),
-            _ => unreachable!(),
+            _ => <u64 as private::Store>::store(off, Self::BITS, data, Self::to_u64(val)),
         }
     }
 
@@ -311,10 +534,20 @@ pub trait Specifier {
         Self::from(v as u64)
     }
     fn from_u64(v: u64) -> Self::Type {
-        Self::from(v as u64)
+        Self::from(v)
     }
     fn from(v: u64) -> Self::Type;
 
+    /// Like `from`, but returns `None` for bit patterns that don't decode.
+    fn checked_from(v: u64) -> Option<Self::Type> {
+        Some(Self::from(v))
+    }
+
+    /// Like `to`, but returns `None` for values that don't fit in `BITS`.
+    fn checked_to(v: Self::Type) -> Option<u64> {
+        Some(Self::to(v))
+    }
+
     fn to_u8(v: Self::Type) -> u8 {
         Self::to(v) as u8
     }
@@ -330,42 +563,46 @@ pub trait Specifier {
     fn to(v: Self::Type) -> u64;
 }
 
+/// Produces arbitrary values of a [`Specifier`] for fuzzing and property
+/// tests; `#[bitfield(arbitrary)]` requires it of every field type.
+#[cfg(feature = "arbitrary")]
+pub trait ArbitrarySpecifier: Specifier {
+    fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<Self::Type>;
+}
+
 pub struct Bn<I, const N: usize>(PhantomData<I>);
 
 impl<I, const N: usize> Specifier for Bn<I, N> where I: TryFrom<u8> + TryFrom<u16> + TryFrom<u32> + TryFrom<u64> + TryInto<u8> + TryInto<u16> + TryInto<u32> + TryInto<u64> {
     const BITS: usize = N;
     type Type = I;
 
-    fn from_u8(v: u8) -> Self::Type {
-        if let Ok(v) = Self::Type::try_from(v) { v } else { panic!() }
-    }
-    fn from_u16(v: u16) -> Self::Type {
-        if let Ok(v) = Self::Type::try_from(v) { v } else { panic!() }
-    }
-    fn from_u32(v: u32) -> Self::Type {
-        if let Ok(v) = Self::Type::try_from(v) { v } else { panic!() }
-    }
-    fn from_u64(v: u64) -> Self::Type {
-        if let Ok(v) = Self::Type::try_from(v) { v } else { panic!() }
-    }
     fn from(v: u64) -> Self::Type {
-        if let Ok(v) = Self::Type::try_from(v) { v } else { panic!() }
+        match Self::checked_from(v) {
+            Some(v) => v,
+            None => __private::conversion_panic::<I>(v, N),
+        }
     }
 
-    fn to_u8(v: Self::Type) -> u8 {
-        if let Ok(v) = Self::Type::try_into(v) { v } else { panic!() }
-    }
-    fn to_u16(v: Self::Type) -> u16 {
-        if let Ok(v) = Self::Type::try_into(v) { v } else { panic!() }
-    }
-    fn to_u32(v: Self::Type) -> u32 {
-        if let Ok(v) = Self::Type::try_into(v) { v } else { panic!() }
+    // `None` for bits beyond the `N` wide field, even if `I` could hold them.
+    fn checked_from(v: u64) -> Option<Self::Type> {
+        if v.checked_shr(N as u32).unwrap_or(0) != 0 {
+            return None;
+        }
+        Self::Type::try_from(v).ok()
     }
-    fn to_u64(v: Self::Type) -> u64 {
-        if let Ok(v) = Self::Type::try_into(v) { v } else { panic!() }
+
+    // `None` for values beyond the `N` wide field, so the narrowing `to_u8`
+    // and friends of the `Specifier::set` arm for `N` never lose bits.
+    fn checked_to(v: Self::Type) -> Option<u64> {
+        let v: u64 = Self::Type::try_into(v).ok()?;
+        (v.checked_shr(N as u32).unwrap_or(0) == 0).then_some(v)
     }
+
     fn to(v: Self::Type) -> u64 {
-        if let Ok(v) = Self::Type::try_into(v) { v } else { panic!() }
+        match Self::checked_to(v) {
+            Some(v) => v,
+            None => panic!("`Bn<{}, {}>` value does not fit in {} bits", core::any::type_name::<I>(), N, N),
+        }
     }
 }
 
@@ -380,6 +617,14 @@ impl Specifier for bool {
         Self::from_u8(v as u8)
     }
 
+    fn checked_from(v: u64) -> Option<Self::Type> {
+        match v {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
     fn to_u8(v: Self::Type) -> u8 {
         if v { 1 } else { 0 }
     }
@@ -388,6 +633,217 @@ impl Specifier for bool {
     }
 }
 
+impl Specifier for char {
+    const BITS: usize = 21;
+    type Type = Self;
+
+    fn from(v: u64) -> Self::Type {
+        match Self::checked_from(v) {
+            Some(c) => c,
+            None => panic!("invalid char value {:#x}", v),
+        }
+    }
+
+    fn checked_from(v: u64) -> Option<Self::Type> {
+        u32::try_from(v).ok().and_then(char::from_u32)
+    }
+
+    fn to(v: Self::Type) -> u64 {
+        v as u64
+    }
+}
+
+impl Specifier for f32 {
+    const BITS: usize = 32;
+    type Type = Self;
+
+    fn from(v: u64) -> Self::Type {
+        f32::from_bits(v as u32)
+    }
+
+    fn to(v: Self::Type) -> u64 {
+        v.to_bits() as u64
+    }
+}
+
+impl Specifier for f64 {
+    const BITS: usize = 64;
+    type Type = Self;
+
+    fn from(v: u64) -> Self::Type {
+        f64::from_bits(v)
+    }
+
+    fn to(v: Self::Type) -> u64 {
+        v.to_bits()
+    }
+}
+
+pub struct Ascii;
+
+impl Specifier for Ascii {
+    const BITS: usize = 7;
+    type Type = char;
+
+    fn from(v: u64) -> Self::Type {
+        match Self::checked_from(v) {
+            Some(c) => c,
+            None => panic!("invalid ASCII value {:#x}", v),
+        }
+    }
+
+    fn checked_from(v: u64) -> Option<Self::Type> {
+        (v < 0x80).then_some(v as u8 as char)
+    }
+
+    fn checked_to(v: Self::Type) -> Option<u64> {
+        v.is_ascii().then_some(v as u64)
+    }
+
+    fn to(v: Self::Type) -> u64 {
+        match Self::checked_to(v) {
+            Some(v) => v,
+            None => panic!("non-ASCII char {:?}", v),
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+macro_rules! impl_arbitrary_specifier {
+    ($($ty:ty)*) => {
+        $(
+            impl<const N: usize> ArbitrarySpecifier for Bn<$ty, N> {
+                fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<$ty> {
+                    u.int_in_range(0..=(<$ty>::MAX >> (<$ty>::BITS as usize - N)))
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "arbitrary")]
+impl_arbitrary_specifier!(u8 u16 u32 u64);
+
+#[cfg(feature = "arbitrary")]
+impl ArbitrarySpecifier for bool {
+    fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<bool> {
+        u.arbitrary()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl ArbitrarySpecifier for char {
+    fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<char> {
+        u.arbitrary()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl ArbitrarySpecifier for f32 {
+    fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<f32> {
+        u.arbitrary()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl ArbitrarySpecifier for f64 {
+    fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<f64> {
+        u.arbitrary()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl ArbitrarySpecifier for Ascii {
+    fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<char> {
+        u.int_in_range(0..=0x7fu8).map(|b| b as char)
+    }
+}
+
+/// An unsigned field of `N` bits, for `N` in `1..=64`.
+///
+/// Unlike the `B1`..`B64` aliases, the width can be a const expression, e.g.
+/// `B<{ ADDR_BITS }>`. The value type is the smallest unsigned integer that
+/// holds `N` bits.
+pub struct B<const N: usize>;
+
+macro_rules! impl_b {
+    ($ty:ty: $($n:literal)*) => {
+        $(
+            impl Specifier for B<$n> {
+                const BITS: usize = $n;
+                type Type = $ty;
+
+                fn from(v: u64) -> $ty {
+                    v as $ty
+                }
+
+                fn checked_from(v: u64) -> Option<$ty> {
+                    (v.checked_shr($n).unwrap_or(0) == 0).then(|| v as $ty)
+                }
+
+                fn to(v: $ty) -> u64 {
+                    v as u64
+                }
+            }
+
+            #[cfg(feature = "arbitrary")]
+            impl ArbitrarySpecifier for B<$n> {
+                fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<$ty> {
+                    u.int_in_range(0..=(<$ty>::MAX >> (<$ty>::BITS as usize - $n)))
+                }
+            }
+        )*
+    };
+}
+
+impl_b!(u8: 1 2 3 4 5 6 7 8);
+impl_b!(u16: 9 10 11 12 13 14 15 16);
+impl_b!(u32: 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32);
+impl_b!(u64: 33 34 35 36 37 38 39 40 41 42 43 44 45 46 47 48 49 50 51 52 53 54 55 56 57 58 59 60 61 62 63 64);
+
+/// An opaque field of `M` bytes, which unlike the other specifiers may be
+/// wider than 64 bits (MACs, UUIDs, keys, ...). Its accessors take and return
+/// `[u8; M]`.
+///
+/// The `from`/`to` conversions only carry the first 8 bytes; use
+/// `Specifier::get`/`set` or [`raw::load_array`] to access the whole value.
+pub struct Bytes<const M: usize>;
+
+impl<const M: usize> Specifier for Bytes<M> {
+    const BITS: usize = M * 8;
+    type Type = [u8; M];
+
+    fn get(off: usize, data: &[u8]) -> Self::Type {
+        raw::load_array(data, off)
+    }
+
+    fn set(off: usize, data: &mut [u8], val: Self::Type) {
+        raw::store_array(data, off, &val)
+    }
+
+    fn from(v: u64) -> Self::Type {
+        let mut out = [0; M];
+        let bytes = v.to_le_bytes();
+        let n = if M < 8 { M } else { 8 };
+        out[..n].copy_from_slice(&bytes[..n]);
+        out
+    }
+
+    fn to(v: Self::Type) -> u64 {
+        let mut bytes = [0; 8];
+        let n = if M < 8 { M } else { 8 };
+        bytes[..n].copy_from_slice(&v[..n]);
+        u64::from_le_bytes(bytes)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<const M: usize> ArbitrarySpecifier for Bytes<M> {
+    fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<[u8; M]> {
+        u.arbitrary()
+    }
+}
+
 pub type B1 = Bn<u8, 1>;
 pub type B2 = Bn<u8, 2>;
 pub type B3 = Bn<u8, 3>;
@@ -455,6 +911,9 @@ pub type B64 = Bn<u64, 64>;
 
 #[cfg(test)]
 mod tests {
+    extern crate std;
+
+    use std::println;
     use super::*;
     use super::private::*;
 
@@ -702,6 +1161,39 @@ mod tests {
         assert_eq!(&[0b0000_0001, 0b0000_0000, 0b0000_0000, 0b0000_0000, 0b1111_1100][..], &data[..]);
     }
 
+    #[test]
+    fn test_raw() {
+        let data = [0b1111_1110, 0b1111_1111, 0b0000_0001];
+        assert_eq!(raw::load(&data, 1, 16), 0b1111_1111_1111_1111);
+        assert_eq!(raw::load(&data, 0, 1), 0);
+
+        let mut data = [0u8; 9];
+        raw::store(&mut data, 1, 64, u64::MAX);
+        assert_eq!(&[0b1111_1110, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0b0000_0001], &data);
+        assert_eq!(raw::load(&data, 1, 64), u64::MAX);
+
+        raw::store(&mut data, 4, 8, 0);
+        assert_eq!(&[0b0000_1110, 0xf0], &data[..2]);
+
+        let mut data = [0u8; 4];
+        raw::store_bytes(&mut data, 1, 2, 0xbeef);
+        assert_eq!(&[0, 0xef, 0xbe, 0], &data);
+        assert_eq!(raw::load_bytes(&data, 1, 2), 0xbeef);
+        assert_eq!(raw::load_bytes(&data, 1, 2), raw::load(&data, 8, 16));
+    }
+
+    #[test]
+    fn test_set37_unaligned() {
+        let mut data = [0xffu8; 6];
+        B37::set(3, &mut data, 0);
+        assert_eq!(&[0b0000_0111, 0, 0, 0, 0, 0xff], &data);
+
+        B37::set(3, &mut data, 0x15_5555_5555);
+        assert_eq!(B37::get(3, &data), 0x15_5555_5555);
+        assert_eq!(raw::load(&data, 3, 37), 0x15_5555_5555);
+        assert_eq!(data[5], 0xff);
+    }
+
     #[test]
     fn test_edge() {
         let mut data = [0u8; 4];
@@ -716,4 +1208,112 @@ mod tests {
         assert_eq!(B13::get(9 + 6, &data), 0x1675);
         //assert_eq!(B4::get(9 + 6 + 13, &data), 0b1110);
     }
+
+    #[test]
+    fn test_try_load_store() {
+        let mut data = [0u8; 2];
+        assert_eq!(raw::try_store(&mut data, 9, 7, 0x55), Ok(()));
+        assert_eq!(raw::try_load(&data, 9, 7), Ok(0x55));
+        let err = raw::try_load(&data, 10, 7).unwrap_err();
+        assert_eq!(err, RangeError { offset: 10, len: 7, size: 16 });
+        assert_eq!(std::format!("{}", err), "bit range 10..17 out of bounds for a 16-bit bitfield (at most 64 bits at a time)");
+        assert_eq!(raw::try_store(&mut data, 10, 7, 0), Err(err));
+        assert_eq!(data, [0, 0x55 << 1]);
+        assert!(raw::try_load(&[0u8; 16], 0, 65).is_err());
+        assert!(raw::try_load(&data, usize::MAX, 2).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "bit range wider than 64 bits or past the end of the data")]
+    fn test_load_out_of_range() {
+        raw::load(&[0u8; 2], 10, 7);
+    }
+
+    #[test]
+    fn test_unchecked() {
+        let mut data = [0u8; 9];
+        raw::store_unchecked(&mut data, 1, 64, u64::MAX);
+        assert_eq!(&[0b1111_1110, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0b0000_0001], &data);
+        assert_eq!(raw::load_unchecked(&data, 1, 64), u64::MAX);
+
+        // Byte-aligned ranges are copied byte-wise.
+        raw::store_unchecked(&mut data, 8, 16, 0xbeef);
+        assert_eq!(&[0xef, 0xbe], &data[1..3]);
+        assert_eq!(raw::load_unchecked(&data, 8, 16), raw::load(&data, 8, 16));
+        assert_eq!(raw::load_unchecked(&data, 3, 13), raw::load(&data, 3, 13));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "bit range wider than 64 bits or past the end of the data")]
+    fn test_unchecked_debug_assert() {
+        raw::load_unchecked(&[0u8; 9], 0, 65);
+    }
+
+    #[test]
+    #[should_panic(expected = "0x100 does not fit in `u8` for a 8-bit field")]
+    fn test_bn_overflow() {
+        <B8 as Specifier>::from(0x100);
+    }
+
+    #[test]
+    fn test_checked_to() {
+        assert_eq!(<B3 as Specifier>::checked_to(7), Some(7));
+        assert_eq!(<B3 as Specifier>::checked_to(8), None);
+        assert_eq!(<Bn<u32, 6> as Specifier>::checked_to(300), None);
+        assert_eq!(<B64 as Specifier>::checked_to(u64::MAX), Some(u64::MAX));
+        assert_eq!(<Ascii as Specifier>::checked_to('z'), Some(0x7a));
+        assert_eq!(<Ascii as Specifier>::checked_to('\u{e9}'), None);
+        assert_eq!(<char as Specifier>::checked_to('A'), Some(0x41));
+    }
+
+    #[test]
+    #[should_panic(expected = "`Bn<u32, 3>` value does not fit in 3 bits")]
+    fn test_bn_to_overflow() {
+        <Bn<u32, 3> as Specifier>::set(0, &mut [0; 4], 300);
+    }
+
+    #[test]
+    fn test_bn_checked_from() {
+        assert_eq!(<B8 as Specifier>::checked_from(0xff), Some(0xff));
+        assert_eq!(<B8 as Specifier>::checked_from(0x100), None);
+        assert_eq!(<B3 as Specifier>::checked_from(7), Some(7));
+        assert_eq!(<B3 as Specifier>::checked_from(9), None);
+        assert_eq!(<B<3> as Specifier>::checked_from(9), None);
+        assert_eq!(<B64 as Specifier>::checked_from(u64::MAX), Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_checked_from() {
+        assert_eq!(<char as Specifier>::checked_from(0x41), Some('A'));
+        assert_eq!(<char as Specifier>::checked_from(0x1_0000_0041), None);
+        assert_eq!(<char as Specifier>::checked_from(0xd800), None);
+        assert_eq!(<bool as Specifier>::checked_from(1), Some(true));
+        assert_eq!(<bool as Specifier>::checked_from(2), None);
+        assert_eq!(<Ascii as Specifier>::checked_from(0x7a), Some('z'));
+        assert_eq!(<Ascii as Specifier>::checked_from(0x80), None);
+    }
+
+    #[test]
+    fn test_char() {
+        let mut data = [0; 3];
+        char::set(0, &mut data, '\u{10ffff}');
+        assert_eq!('\u{10ffff}', char::get(0, &data));
+
+        let mut data = [0];
+        Ascii::set(1, &mut data, 'z');
+        assert_eq!('z', Ascii::get(1, &data));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_char_surrogate() {
+        <char as Specifier>::from(0xd800);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_ascii_non_ascii() {
+        <Ascii as Specifier>::to('\u{e9}');
+    }
 }
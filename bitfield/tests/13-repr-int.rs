@@ -0,0 +1,40 @@
+// A #[bitfield(repr = uN)] struct is backed by a single integer instead of a
+// byte array, so every accessor is one shift-and-mask on the whole word. The
+// struct converts to and from the backing integer.
+
+use bitfield::*;
+
+#[bitfield(repr = u32)]
+pub struct Register {
+    enable: bool,
+    mode: B3,
+    prescaler: B12,
+    counter: B16,
+}
+
+fn main() {
+    assert_eq!(std::mem::size_of::<Register>(), 4);
+
+    assert_eq!(u32::from(Register::new()), 0);
+
+    let mut reg = Register::new();
+    reg.set_enable(true);
+    reg.set_mode(0b101);
+    reg.set_prescaler(0xabc);
+    reg.set_counter(0xffff);
+    assert!(reg.get_enable());
+    assert_eq!(reg.get_mode(), 0b101);
+    assert_eq!(reg.get_prescaler(), 0xabc);
+    assert_eq!(reg.get_counter(), 0xffff);
+
+    reg.set_mode(0);
+    assert_eq!(reg.get_mode(), 0);
+    assert_eq!(reg.get_prescaler(), 0xabc);
+
+    let reg = Register::from(0xffff_abc1);
+    assert!(reg.get_enable());
+    assert_eq!(reg.get_mode(), 0);
+    assert_eq!(reg.get_prescaler(), 0xabc);
+    assert_eq!(reg.get_counter(), 0xffff);
+    assert_eq!(u32::from(reg), 0xffff_abc1);
+}
@@ -0,0 +1,12 @@
+// The fields of a #[bitfield(repr = uN)] struct must fit in the backing
+// integer.
+
+use bitfield::*;
+
+#[bitfield(repr = u16)]
+pub struct TooWide {
+    a: B8,
+    b: B16,
+}
+
+fn main() {}
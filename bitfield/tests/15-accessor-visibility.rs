@@ -0,0 +1,30 @@
+// Generated getters and setters take the visibility of the field they access,
+// so private bits are not exposed outside the defining module.
+
+mod device {
+    use bitfield::*;
+
+    #[bitfield]
+    pub struct Status {
+        pub ready: bool,
+        pub(crate) error: bool,
+        reserved: B6,
+    }
+
+    impl Status {
+        pub fn reserved_is_zero(&self) -> bool {
+            self.get_reserved() == 0
+        }
+    }
+}
+
+use device::Status;
+
+fn main() {
+    let mut status = Status::new();
+    status.set_ready(true);
+    status.set_error(true);
+    assert!(status.get_ready());
+    assert!(status.get_error());
+    assert!(status.reserved_is_zero());
+}
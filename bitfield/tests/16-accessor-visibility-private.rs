@@ -0,0 +1,18 @@
+// Accessors for a private field are not reachable from outside the module.
+
+mod device {
+    use bitfield::*;
+
+    #[bitfield]
+    pub struct Status {
+        pub ready: bool,
+        reserved: B7,
+    }
+}
+
+use device::Status;
+
+fn main() {
+    let status = Status::new();
+    let _ = status.get_reserved();
+}
@@ -0,0 +1,44 @@
+// Accessors of fields using the built-in B1..B64 and bool specifiers are const
+// fn, so bitfield values can be assembled at compile time. Fields of other
+// Specifier types (such as enums) keep ordinary accessors.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Descriptor {
+    valid: bool,
+    kind: B7,
+    length: B24,
+}
+
+#[bitfield(repr = u16)]
+pub struct Flags {
+    a: B4,
+    b: B12,
+}
+
+const DESCRIPTOR: Descriptor = {
+    let mut desc = Descriptor::new();
+    desc.set_valid(true);
+    desc.set_kind(0x5a);
+    desc.set_length(0x12_3456);
+    desc
+};
+
+const LENGTH: u32 = DESCRIPTOR.get_length();
+
+const FLAGS: Flags = {
+    let mut flags = Flags::new();
+    flags.set_b(0xabc);
+    flags
+};
+
+fn main() {
+    assert!(DESCRIPTOR.get_valid());
+    assert_eq!(DESCRIPTOR.get_kind(), 0x5a);
+    assert_eq!(LENGTH, 0x12_3456);
+
+    assert_eq!(FLAGS.get_a(), 0);
+    assert_eq!(FLAGS.get_b(), 0xabc);
+    assert_eq!(u16::from(FLAGS), 0xabc0);
+}
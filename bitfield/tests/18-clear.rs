@@ -0,0 +1,23 @@
+// clear() resets every bit of a bitfield back to zero so that a value can be
+// reused.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Packet {
+    flag: bool,
+    kind: B7,
+    payload: B16,
+}
+
+fn main() {
+    let mut packet = Packet::new();
+    packet.set_flag(true);
+    packet.set_kind(0x7f);
+    packet.set_payload(0xbeef);
+
+    packet.clear();
+    assert!(!packet.get_flag());
+    assert_eq!(packet.get_kind(), 0);
+    assert_eq!(packet.get_payload(), 0);
+}
@@ -0,0 +1,27 @@
+// modify() stages updates of several fields on a copy and writes the result
+// back in one go.
+
+use bitfield::*;
+
+#[bitfield(repr = u32)]
+pub struct Control {
+    enable: bool,
+    mode: B3,
+    divider: B12,
+    reserved: B16,
+}
+
+fn main() {
+    let mut ctrl = Control::new();
+    ctrl.set_divider(0x123);
+
+    ctrl.modify(|w| {
+        assert_eq!(w.get_divider(), 0x123);
+        w.set_enable(true);
+        w.set_mode(0b110);
+    });
+    assert!(ctrl.get_enable());
+    assert_eq!(ctrl.get_mode(), 0b110);
+    assert_eq!(ctrl.get_divider(), 0x123);
+    assert_eq!(u32::from(ctrl), 0x123d);
+}
@@ -0,0 +1,38 @@
+// #[bitfield(repr = uN, atomic)] additionally generates an AtomicFoo wrapper
+// backed by the matching AtomicUN type, with per-field lock-free accessors.
+
+use bitfield::*;
+use std::sync::atomic::Ordering;
+
+#[bitfield(repr = u16, atomic)]
+pub struct Flags {
+    busy: bool,
+    error: bool,
+    count: B14,
+}
+
+fn main() {
+    let mut init = Flags::new();
+    init.set_count(3);
+    let flags = AtomicFlags::new(init);
+
+    assert!(!flags.load_busy(Ordering::SeqCst));
+    assert_eq!(flags.load_count(Ordering::SeqCst), 3);
+
+    let prev = flags.fetch_update_busy(Ordering::SeqCst, Ordering::SeqCst, |busy| {
+        if busy { None } else { Some(true) }
+    });
+    assert_eq!(prev, Ok(false));
+    assert_eq!(flags.fetch_update_busy(Ordering::SeqCst, Ordering::SeqCst, |_| None), Err(true));
+
+    let prev = flags.fetch_update_count(Ordering::SeqCst, Ordering::SeqCst, |n| Some(n + 1));
+    assert_eq!(prev, Ok(3));
+
+    let snapshot = flags.load(Ordering::SeqCst);
+    assert!(snapshot.get_busy());
+    assert!(!snapshot.get_error());
+    assert_eq!(snapshot.get_count(), 4);
+
+    flags.store(Flags::new(), Ordering::SeqCst);
+    assert_eq!(u16::from(flags.into_inner()), 0);
+}
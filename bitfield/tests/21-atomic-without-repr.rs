@@ -0,0 +1,11 @@
+// The atomic wrapper is only available for integer-backed bitfields.
+
+use bitfield::*;
+
+#[bitfield(atomic)]
+pub struct Flags {
+    busy: bool,
+    count: B7,
+}
+
+fn main() {}
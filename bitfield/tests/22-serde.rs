@@ -0,0 +1,80 @@
+// With the `serde` feature, #[bitfield(serde = "bytes")] structs implement
+// Serialize and Deserialize as the raw packed bytes, or as the backing integer
+// for integer-backed structs, and #[bitfield(serde = "fields")] ones as a map
+// of decoded field values, rejecting values that don't fit their field.
+// Structs without `serde` are left alone, free to implement the traits
+// themselves.
+
+use bitfield::*;
+
+#[bitfield(serde = "bytes")]
+pub struct Raw {
+    a: B4,
+    b: B12,
+}
+
+#[bitfield(repr = u16, serde = "bytes")]
+pub struct Word {
+    a: B4,
+    b: B12,
+}
+
+#[bitfield(serde = "fields")]
+pub struct Decoded {
+    enabled: bool,
+    mode: Mode,
+    count: B5,
+}
+
+#[bitfield]
+pub struct Custom {
+    a: B4,
+    b: B4,
+}
+
+impl serde::Serialize for Custom {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str("custom")
+    }
+}
+
+#[derive(BitfieldSpecifier, serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+pub enum Mode {
+    Slow = 0,
+    Fast = 1,
+    Turbo = 2,
+    Off = 3,
+}
+
+fn main() {
+    let mut raw = Raw::new();
+    raw.set_a(0x1);
+    raw.set_b(0xabc);
+    let json = serde_json::to_string(&raw).unwrap();
+    assert_eq!(json, "[193,171]");
+    let raw: Raw = serde_json::from_str(&json).unwrap();
+    assert_eq!(raw.get_b(), 0xabc);
+    assert!(serde_json::from_str::<Raw>("[1,2,3]").is_err());
+
+    let mut word = Word::new();
+    word.set_b(0x001);
+    assert_eq!(serde_json::to_string(&word).unwrap(), "16");
+    let word: Word = serde_json::from_str("16").unwrap();
+    assert_eq!(word.get_b(), 1);
+
+    let mut decoded = Decoded::new();
+    decoded.set_enabled(true);
+    decoded.set_mode(Mode::Turbo);
+    decoded.set_count(17);
+    let json = serde_json::to_string(&decoded).unwrap();
+    assert_eq!(json, r#"{"enabled":true,"mode":"Turbo","count":17}"#);
+    let decoded: Decoded = serde_json::from_str(&json).unwrap();
+    assert!(decoded.get_enabled());
+    assert_eq!(decoded.get_mode(), Mode::Turbo);
+    assert_eq!(decoded.get_count(), 17);
+
+    let err = serde_json::from_str::<Decoded>(r#"{"enabled":true,"mode":"Fast","count":33}"#).err().unwrap();
+    assert!(err.to_string().starts_with("value out of range for field `count` of 5 bits"));
+
+    assert_eq!(serde_json::to_string(&Custom::new()).unwrap(), r#""custom""#);
+}
@@ -0,0 +1,25 @@
+// With the `defmt` feature, #[bitfield(defmt)] structs implement
+// defmt::Format and log every field by name and decoded value.
+
+use bitfield::*;
+
+#[bitfield(defmt)]
+pub struct Status {
+    ready: bool,
+    mode: Mode,
+    count: B5,
+}
+
+#[derive(BitfieldSpecifier, defmt::Format)]
+pub enum Mode {
+    Slow = 0,
+    Fast = 1,
+    Turbo = 2,
+    Off = 3,
+}
+
+fn assert_format<T: defmt::Format>() {}
+
+fn main() {
+    assert_format::<Status>();
+}
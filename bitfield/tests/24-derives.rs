@@ -0,0 +1,38 @@
+// Derives written after #[bitfield] are forwarded onto the generated struct.
+// Derives written before the attribute would see the original field types
+// instead, so #[bitfield(derive(...))] is provided as an order-independent
+// spelling.
+
+use bitfield::*;
+use std::collections::HashSet;
+
+#[bitfield]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct After {
+    a: B3,
+    b: B5,
+}
+
+#[bitfield(repr = u16, derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default))]
+pub struct Listed {
+    a: B4,
+    b: B12,
+}
+
+fn main() {
+    let mut x = After::new();
+    x.set_b(9);
+    let y = x;
+    assert_eq!(x, y);
+    assert_ne!(x, After::default());
+
+    let mut set = HashSet::new();
+    set.insert(x);
+    assert!(set.contains(&y));
+
+    let mut p = Listed::default();
+    p.set_a(1);
+    let q = p.clone();
+    assert_eq!(p, q);
+    assert_eq!(format!("{:?}", Listed::new()), "Listed { data: 0 }");
+}
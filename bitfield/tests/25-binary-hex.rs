@@ -0,0 +1,31 @@
+// Bitfields implement fmt::Binary and fmt::LowerHex, printing the packed value
+// MSB first with every bit of the storage, as a datasheet would show it.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Header {
+    a: B4,
+    b: B12,
+}
+
+#[bitfield(repr = u16)]
+pub struct Word {
+    a: B4,
+    b: B12,
+}
+
+fn main() {
+    let mut header = Header::new();
+    header.set_a(0x1);
+    header.set_b(0x0ab);
+    assert_eq!(format!("{:b}", header), "0000101010110001");
+    assert_eq!(format!("{:#b}", header), "0b0000101010110001");
+    assert_eq!(format!("{:x}", header), "0ab1");
+    assert_eq!(format!("{:#x}", header), "0x0ab1");
+
+    let mut word = Word::new();
+    word.set_a(0xf);
+    assert_eq!(format!("{:b}", word), "0000000000001111");
+    assert_eq!(format!("{:#x}", word), "0x000f");
+}
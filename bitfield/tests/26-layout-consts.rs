@@ -0,0 +1,39 @@
+// Each field's bit offset and width are exposed as associated constants named
+// after the field, so external code can build masks from the same layout.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Descriptor {
+    pub valid: bool,
+    pub kind: B7,
+    pub length: B24,
+    pub r#type: Kind,
+    reserved: B5,
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+pub enum Kind {
+    A = 0,
+    B = 1,
+    C = 2,
+    D = 3,
+    E = 4,
+    F = 5,
+    G = 6,
+    H = 7,
+}
+
+const LENGTH_MASK: u64 = ((1 << Descriptor::LENGTH_BITS) - 1) << Descriptor::LENGTH_OFFSET;
+
+fn main() {
+    assert_eq!(Descriptor::VALID_OFFSET, 0);
+    assert_eq!(Descriptor::VALID_BITS, 1);
+    assert_eq!(Descriptor::KIND_OFFSET, 1);
+    assert_eq!(Descriptor::KIND_BITS, 7);
+    assert_eq!(Descriptor::LENGTH_OFFSET, 8);
+    assert_eq!(Descriptor::LENGTH_BITS, 24);
+    assert_eq!(Descriptor::TYPE_OFFSET, 32);
+    assert_eq!(Descriptor::TYPE_BITS, 3);
+    assert_eq!(LENGTH_MASK, 0xffff_ff00);
+}
@@ -0,0 +1,26 @@
+// FIELDS lists the name, offset and width of every field so that tools can
+// walk a layout generically. As it names private fields as well, it is
+// `pub(crate)` whatever the visibility of the struct.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Descriptor {
+    valid: bool,
+    kind: B7,
+    length: B24,
+}
+
+fn main() {
+    assert_eq!(
+        Descriptor::FIELDS,
+        &[
+            FieldInfo { name: "valid", offset: 0, bits: 1 },
+            FieldInfo { name: "kind", offset: 1, bits: 7 },
+            FieldInfo { name: "length", offset: 8, bits: 24 },
+        ],
+    );
+
+    let total = Descriptor::FIELDS.iter().map(|f| f.bits).sum::<usize>();
+    assert_eq!(total, 32);
+}
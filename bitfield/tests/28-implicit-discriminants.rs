@@ -0,0 +1,29 @@
+// Enums deriving BitfieldSpecifier don't need to spell out discriminants; the
+// variants take the values 0..n in declaration order.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Packet {
+    priority: Priority,
+    reserved: B6,
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+    Urgent,
+}
+
+fn main() {
+    assert_eq!(<Priority as Specifier>::BITS, 2);
+
+    let mut packet = Packet::new();
+    assert_eq!(packet.get_priority(), Priority::Low);
+
+    packet.set_priority(Priority::High);
+    assert_eq!(packet.get_priority(), Priority::High);
+    assert_eq!(format!("{:b}", packet), "00000010");
+}
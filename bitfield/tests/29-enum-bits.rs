@@ -0,0 +1,30 @@
+// #[bits = N] on a BitfieldSpecifier enum fixes the width of the field instead
+// of inferring it from the largest discriminant, leaving room for variants a
+// later revision may add.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Frame {
+    version: Version,
+    flags: B4,
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+#[bits = 4]
+pub enum Version {
+    V1 = 1,
+    V2 = 2,
+    V3 = 3,
+}
+
+fn main() {
+    assert_eq!(<Version as Specifier>::BITS, 4);
+    assert_eq!(Frame::FLAGS_OFFSET, 4);
+
+    let mut frame = Frame::new();
+    frame.set_version(Version::V3);
+    frame.set_flags(0xf);
+    assert_eq!(frame.get_version(), Version::V3);
+    assert_eq!(frame.get_flags(), 0xf);
+}
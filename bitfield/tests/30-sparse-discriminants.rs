@@ -0,0 +1,15 @@
+// Without #[bits = N], the variants of a BitfieldSpecifier enum must cover
+// every bit pattern of the inferred width. Here four variants would need three
+// bits, leaving four patterns without a variant to decode into.
+
+use bitfield::*;
+
+#[derive(BitfieldSpecifier)]
+pub enum Sparse {
+    A = 0,
+    B = 1,
+    C = 2,
+    D = 4,
+}
+
+fn main() {}
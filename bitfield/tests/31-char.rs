@@ -0,0 +1,31 @@
+// char fields: `char` takes 21 bits and holds any Unicode scalar value, while
+// `Ascii` packs a 7-bit ASCII character. Invalid raw bits are rejected by the
+// getter.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Tag {
+    a: Ascii,
+    b: Ascii,
+    c: Ascii,
+    d: Ascii,
+    sym: char,
+    reserved: B7,
+}
+
+fn main() {
+    assert_eq!(<char as Specifier>::BITS, 21);
+    assert_eq!(<Ascii as Specifier>::BITS, 7);
+
+    let mut tag = Tag::new();
+    tag.set_a('R');
+    tag.set_b('I');
+    tag.set_c('F');
+    tag.set_d('F');
+    tag.set_sym('\u{1F980}');
+
+    let s: String = [tag.get_a(), tag.get_b(), tag.get_c(), tag.get_d()].iter().collect();
+    assert_eq!(s, "RIFF");
+    assert_eq!(tag.get_sym(), '\u{1F980}');
+}
@@ -0,0 +1,57 @@
+// Field types defined outside the bitfield crate only need `BITS`, `Type` and
+// the `from`/`to` conversions. The `raw` module exposes the underlying bit
+// access for code that wants to work on packed bytes directly.
+
+use bitfield::*;
+
+// Two-digit binary coded decimal.
+pub struct Bcd;
+
+impl Specifier for Bcd {
+    const BITS: usize = 8;
+    type Type = u8;
+
+    fn from(v: u64) -> u8 {
+        (v >> 4) as u8 * 10 + (v & 0xf) as u8
+    }
+
+    fn to(v: u8) -> u64 {
+        ((v / 10) << 4 | v % 10) as u64
+    }
+}
+
+// Unsigned Q4.4 fixed point.
+pub struct UFix4;
+
+impl Specifier for UFix4 {
+    const BITS: usize = 8;
+    type Type = f32;
+
+    fn from(v: u64) -> f32 {
+        v as f32 / 16.0
+    }
+
+    fn to(v: f32) -> u64 {
+        (v * 16.0) as u64
+    }
+}
+
+#[bitfield]
+pub struct Reading {
+    day: Bcd,
+    level: UFix4,
+}
+
+fn main() {
+    let mut reading = Reading::new();
+    reading.set_day(31);
+    reading.set_level(2.75);
+    assert_eq!(reading.get_day(), 31);
+    assert_eq!(reading.get_level(), 2.75);
+
+    let mut data = [0u8; 2];
+    Bcd::set(0, &mut data, 42);
+    assert_eq!(raw::load(&data, 0, 8), 0x42);
+    raw::store(&mut data, 8, 8, 0x2c);
+    assert_eq!(UFix4::get(8, &data), 2.75);
+}
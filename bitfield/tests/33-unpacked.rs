@@ -0,0 +1,44 @@
+// #[bitfield(unpacked)] also emits a plain `FooFields` struct holding each
+// field's value type, with `pack` and `unpack` converting between the two, so
+// most code can work with ordinary fields and only pack at the I/O boundary.
+
+use bitfield::*;
+
+#[bitfield(unpacked)]
+pub struct Header {
+    pub version: B3,
+    pub mode: Mode,
+    pub urgent: bool,
+    pub length: B10,
+    pub reserved: B8,
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+pub enum Mode {
+    Idle = 0,
+    Send = 1,
+    Recv = 2,
+    Both = 3,
+}
+
+fn main() {
+    let header = Header::pack(HeaderFields {
+        version: 5,
+        mode: Mode::Recv,
+        urgent: true,
+        length: 1000,
+        reserved: 0,
+    });
+    assert_eq!(header.get_version(), 5);
+    assert_eq!(header.get_mode(), Mode::Recv);
+    assert!(header.get_urgent());
+    assert_eq!(header.get_length(), 1000);
+
+    let mut fields = header.unpack();
+    fields.length += 1;
+    fields.mode = Mode::Both;
+    let header = Header::pack(fields);
+    assert_eq!(header.get_length(), 1001);
+    assert_eq!(header.get_mode(), Mode::Both);
+    assert_eq!(header.get_version(), 5);
+}
@@ -0,0 +1,56 @@
+// #[bitfield(view)] generates `FooView<'a>`, a borrowed view with the same
+// getters that reads fields in place from a byte buffer. Construction checks
+// that the buffer is long enough.
+
+use bitfield::*;
+
+#[bitfield(view)]
+pub struct Header {
+    pub version: B4,
+    pub kind: Kind,
+    pub flags: B2,
+    pub length: B16,
+    pub ttl: B8,
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+pub enum Kind {
+    Data = 0,
+    Ack = 1,
+    Nack = 2,
+    Ping = 3,
+}
+
+#[bitfield(repr = u16, view)]
+pub struct Small {
+    pub a: B4,
+    pub b: B12,
+}
+
+fn main() {
+    let mut header = Header::new();
+    header.set_version(4);
+    header.set_kind(Kind::Ack);
+    header.set_length(1500);
+    header.set_ttl(64);
+
+    let mut frame = [0u8; 8];
+    let bytes = [0x14, 0xdc, 0x05, 0x40];
+    frame[..4].copy_from_slice(&bytes);
+    assert_eq!(format!("{:x}", header), "4005dc14");
+
+    let view = HeaderView::new(&frame).unwrap();
+    assert_eq!(view.get_version(), 4);
+    assert_eq!(view.get_kind(), Kind::Ack);
+    assert_eq!(view.get_flags(), 0);
+    assert_eq!(view.get_length(), 1500);
+    assert_eq!(view.get_ttl(), 64);
+
+    assert!(HeaderView::new(&frame[..3]).is_none());
+
+    let small = Small::from(0xabc5u16);
+    let bytes = u16::from(small).to_le_bytes();
+    let view = SmallView::new(&bytes).unwrap();
+    assert_eq!(view.get_a(), 5);
+    assert_eq!(view.get_b(), 0xabc);
+}
@@ -0,0 +1,33 @@
+// #[bitfield(view)] also generates `FooViewMut<'a>`, which has the setters as
+// well and patches fields in place inside a larger buffer, leaving the bytes
+// around it untouched.
+
+use bitfield::*;
+
+#[bitfield(view)]
+pub struct Header {
+    pub version: B4,
+    pub flags: B4,
+    pub ttl: B8,
+    pub checksum: B16,
+}
+
+fn main() {
+    let mut frame = [0xffu8; 10];
+    frame[2..6].copy_from_slice(&[0x41, 0x40, 0x00, 0x00]);
+
+    let mut view = HeaderViewMut::new(&mut frame[2..]).unwrap();
+    assert_eq!(view.get_version(), 1);
+    assert_eq!(view.get_flags(), 4);
+    assert_eq!(view.get_ttl(), 64);
+
+    let ttl = view.get_ttl();
+    view.set_ttl(ttl - 1);
+    view.set_checksum(0xbeef);
+    assert_eq!(view.as_view().get_ttl(), 63);
+
+    assert_eq!(frame, [0xff, 0xff, 0x41, 0x3f, 0xef, 0xbe, 0xff, 0xff, 0xff, 0xff]);
+
+    let mut short = [0u8; 3];
+    assert!(HeaderViewMut::new(&mut short).is_none());
+}
@@ -0,0 +1,52 @@
+// `update` batches several field writes: the closure records them on a
+// writer, and they are applied to the bitfield with a single
+// read-modify-write. Fields that are not written keep their value.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Control {
+    enable: bool,
+    mode: Mode,
+    divider: B13,
+    count: B16,
+}
+
+#[bitfield(repr = u32)]
+pub struct Register {
+    enable: bool,
+    mode: Mode,
+    divider: B13,
+    count: B16,
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+pub enum Mode {
+    Slow = 0,
+    Normal = 1,
+    Fast = 2,
+    Turbo = 3,
+}
+
+fn main() {
+    let mut control = Control::new();
+    control.set_count(0xffff);
+    control.update(|w| {
+        w.enable(true).mode(Mode::Fast);
+        w.divider(4095);
+    });
+    assert!(control.get_enable());
+    assert_eq!(control.get_mode(), Mode::Fast);
+    assert_eq!(control.get_divider(), 4095);
+    assert_eq!(control.get_count(), 0xffff);
+
+    let mut register = Register::from(u32::MAX);
+    register.update(|w| {
+        w.enable(false);
+        w.mode(Mode::Normal);
+    });
+    assert!(!register.get_enable());
+    assert_eq!(register.get_mode(), Mode::Normal);
+    assert_eq!(register.get_divider(), 0x1fff);
+    assert_eq!(register.get_count(), 0xffff);
+}
@@ -0,0 +1,32 @@
+// Fields whose offset and width are both multiples of 8 are read and written
+// as whole bytes. They must still round-trip and leave their neighbours alone.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Packet {
+    kind: B8,
+    length: B16,
+    flag: bool,
+    pad: B7,
+    id: B32,
+}
+
+fn main() {
+    let mut packet = Packet::new();
+    packet.set_flag(true);
+    packet.set_pad(0x7f);
+    packet.set_kind(0xa5);
+    packet.set_length(0x1234);
+    packet.set_id(0xdead_beef);
+
+    assert_eq!(packet.get_kind(), 0xa5);
+    assert_eq!(packet.get_length(), 0x1234);
+    assert!(packet.get_flag());
+    assert_eq!(packet.get_pad(), 0x7f);
+    assert_eq!(packet.get_id(), 0xdead_beef);
+    assert_eq!(format!("{:x}", packet), "deadbeefff1234a5");
+
+    packet.set_length(0);
+    assert_eq!(format!("{:x}", packet), "deadbeefff0000a5");
+}
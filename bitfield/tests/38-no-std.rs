@@ -0,0 +1,47 @@
+// The runtime crate is #![no_std] and the generated code only refers to
+// `core`, so bitfields work in crates without the std prelude. (std is linked
+// here only to provide the binary's entry point.)
+
+#![no_std]
+
+extern crate std;
+
+use bitfield::*;
+
+#[bitfield(view)]
+pub struct Status {
+    ready: bool,
+    state: State,
+    code: B5,
+    ext: Ext,
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+pub enum State {
+    Off = 0,
+    On = 1,
+    Fault = 2,
+    Unknown = 3,
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+#[bits = 8]
+pub enum Ext {
+    None = 0,
+    Some = 1,
+}
+
+fn main() {
+    let mut status = Status::new();
+    status.set_ready(true);
+    status.set_state(State::Fault);
+    status.set_code(17);
+    status.set_ext(Ext::Some);
+    status.update(|w| {
+        w.code(18);
+    });
+    assert!(status.get_ready());
+    assert_eq!(status.get_state(), State::Fault);
+    assert_eq!(status.get_code(), 18);
+    assert_eq!(status.get_ext(), Ext::Some);
+}
@@ -0,0 +1,30 @@
+// Doc comments, #[cfg] and #[deprecated] on a field are copied onto the
+// accessors generated for it, so the generated API documents itself and a
+// deprecated field warns where it is used.
+
+use bitfield::*;
+
+#[bitfield(view)]
+pub struct Config {
+    /// Enables the device.
+    pub enable: bool,
+    /// Clock divider, in powers of two.
+    #[cfg(all())]
+    pub divider: B3,
+    #[deprecated(note = "use `divider` instead")]
+    pub prescaler: B4,
+}
+
+fn main() {
+    let mut config = Config::new();
+    config.set_enable(true);
+    config.set_divider(5);
+    assert!(config.get_enable());
+    assert_eq!(config.get_divider(), 5);
+
+    #[allow(deprecated)]
+    {
+        config.set_prescaler(3);
+        assert_eq!(config.get_prescaler(), 3);
+    }
+}
@@ -0,0 +1,18 @@
+// A #[deprecated] field makes its accessors deprecated too.
+
+#![deny(deprecated)]
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Config {
+    pub enable: bool,
+    #[deprecated(note = "use `divider` instead")]
+    pub prescaler: B7,
+}
+
+fn main() {
+    let mut config = Config::new();
+    config.set_enable(true);
+    config.set_prescaler(3);
+}
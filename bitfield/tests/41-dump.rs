@@ -0,0 +1,51 @@
+// `dump` renders every field with its bit range and current raw value in
+// binary and hex, which helps when comparing a register against a datasheet.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Control {
+    enable: bool,
+    mode: Mode,
+    divider: B5,
+    count: B8,
+}
+
+#[bitfield(repr = u8)]
+pub struct Flags {
+    a: B3,
+    b: B5,
+}
+
+#[derive(BitfieldSpecifier, Debug)]
+pub enum Mode {
+    Slow = 0,
+    Normal = 1,
+    Fast = 2,
+    Turbo = 3,
+}
+
+fn main() {
+    let mut control = Control::new();
+    control.set_enable(true);
+    control.set_mode(Mode::Fast);
+    control.set_divider(9);
+    control.set_count(0xa5);
+
+    let expected = "\
+Control {
+    enable  [0] = 0b1 (0x1)
+    mode    [2:1] = 0b10 (0x2)
+    divider [7:3] = 0b01001 (0x9)
+    count   [15:8] = 0b10100101 (0xa5)
+}";
+    assert_eq!(control.dump().to_string(), expected);
+
+    let flags = Flags::from(0b1111_0010);
+    let expected = "\
+Flags {
+    a [2:0] = 0b010 (0x2)
+    b [7:3] = 0b11110 (0x1e)
+}";
+    assert_eq!(flags.dump().to_string(), expected);
+}
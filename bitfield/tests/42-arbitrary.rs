@@ -0,0 +1,42 @@
+// With the `arbitrary` feature, #[bitfield(arbitrary)] implements
+// `arbitrary::Arbitrary` by drawing every field from its `ArbitrarySpecifier`.
+// BitfieldSpecifier enums only ever produce one of their variants, so the
+// generated values always decode, and one without variants reports
+// `EmptyChoose`.
+
+use arbitrary::{Arbitrary, Unstructured};
+use bitfield::*;
+
+#[bitfield(arbitrary)]
+pub struct Frame {
+    urgent: bool,
+    kind: Kind,
+    length: B12,
+    tag: Ascii,
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+#[bits = 4]
+pub enum Kind {
+    Data = 1,
+    Ack = 4,
+    Nack = 9,
+}
+
+#[derive(BitfieldSpecifier)]
+#[bits = 2]
+pub enum Never {}
+
+fn main() {
+    let bytes = (0..=255u8).cycle().step_by(7).take(4096).collect::<Vec<_>>();
+    let mut u = Unstructured::new(&bytes);
+    for _ in 0..256 {
+        let frame = Frame::arbitrary(&mut u).unwrap();
+        assert!(matches!(frame.get_kind(), Kind::Data | Kind::Ack | Kind::Nack));
+        assert!(frame.get_length() < 4096);
+        assert!(frame.get_tag().is_ascii());
+    }
+
+    let err = <Never as ArbitrarySpecifier>::arbitrary(&mut u).err().unwrap();
+    assert!(matches!(err, arbitrary::Error::EmptyChoose));
+}
@@ -0,0 +1,48 @@
+// Fields under #[cfg(...)] only take up bits when they are compiled in; the
+// fields after them move up otherwise. The layout constants, `FIELDS` and
+// every generated accessor follow the same configuration, so one definition
+// can describe several hardware revisions.
+
+use bitfield::*;
+
+#[bitfield(unpacked, view)]
+pub struct Reg {
+    enable: bool,
+    #[cfg(target_pointer_width = "64")]
+    wide: B15,
+    #[cfg(not(target_pointer_width = "64"))]
+    narrow: B7,
+    #[cfg(any())]
+    ghost: DoesNotExist,
+    tail: B8,
+}
+
+fn main() {
+    let mut reg = Reg::new();
+    reg.set_enable(true);
+    reg.set_tail(0xa5);
+    reg.update(|w| {
+        w.tail(0x5a);
+    });
+    assert_eq!(Reg::GHOST_BITS, 0);
+    assert_eq!(Reg::FIELDS.len(), 3);
+
+    #[cfg(target_pointer_width = "64")]
+    {
+        reg.set_wide(0x7fff);
+        assert_eq!(Reg::TAIL_OFFSET, 16);
+        assert_eq!(std::mem::size_of::<Reg>(), 3);
+        assert_eq!(reg.unpack().wide, 0x7fff);
+    }
+    #[cfg(not(target_pointer_width = "64"))]
+    {
+        reg.set_narrow(0x7f);
+        assert_eq!(Reg::TAIL_OFFSET, 8);
+        assert_eq!(std::mem::size_of::<Reg>(), 2);
+        assert_eq!(reg.unpack().narrow, 0x7f);
+    }
+
+    assert!(reg.get_enable());
+    assert_eq!(reg.get_tail(), 0x5a);
+    assert_eq!(Reg::pack(reg.unpack()).get_tail(), 0x5a);
+}
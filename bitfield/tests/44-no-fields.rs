@@ -0,0 +1,16 @@
+// A bitfield without fields has no storage to describe and is rejected up
+// front, whether written with braces or as a unit struct. Tuple structs have
+// no names to derive accessors from.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Empty {}
+
+#[bitfield]
+pub struct Unit;
+
+#[bitfield]
+pub struct Tuple(B4, B4);
+
+fn main() {}
@@ -0,0 +1,41 @@
+// Field types must be between 1 and 64 bits wide. A custom Specifier outside
+// that range is reported on the field that uses it.
+
+use bitfield::*;
+
+pub struct Nothing;
+
+impl Specifier for Nothing {
+    const BITS: usize = 0;
+    type Type = ();
+
+    fn from(_: u64) {}
+
+    fn to(_: ()) -> u64 {
+        0
+    }
+}
+
+pub struct Huge;
+
+impl Specifier for Huge {
+    const BITS: usize = 72;
+    type Type = u128;
+
+    fn from(v: u64) -> u128 {
+        v as u128
+    }
+
+    fn to(v: u128) -> u64 {
+        v as u64
+    }
+}
+
+#[bitfield]
+pub struct Packet {
+    head: B8,
+    none: Nothing,
+    body: Huge,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+// Using a type that does not implement Specifier points at the field and
+// lists what can be used instead.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Packet {
+    head: B8,
+    body: u8,
+}
+
+fn main() {}
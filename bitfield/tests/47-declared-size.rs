@@ -0,0 +1,28 @@
+// #[bitfield(bytes = N)] or #[bitfield(bits = N)] states the expected total
+// size, so a layout change is caught at the definition. The computed size is
+// not part of the generated API, so the struct's own `BITS` doesn't clash.
+
+use bitfield::*;
+
+#[bitfield(bytes = 4)]
+pub struct Header {
+    version: B4,
+    length: B12,
+    checksum: B16,
+}
+
+#[bitfield(bits = 16, repr = u16)]
+pub struct Word {
+    low: B8,
+    high: B8,
+}
+
+impl Word {
+    const BITS: u8 = 3;
+}
+
+fn main() {
+    assert_eq!(std::mem::size_of::<Header>(), 4);
+    assert_eq!(std::mem::size_of::<Word>(), 2);
+    assert_eq!(Word::BITS, 3);
+}
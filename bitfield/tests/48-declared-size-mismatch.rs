@@ -0,0 +1,18 @@
+// A declared size that does not match the sum of the field widths is an
+// error on the declaration.
+
+use bitfield::*;
+
+#[bitfield(bytes = 4)]
+pub struct Header {
+    version: B4,
+    length: B12,
+    checksum: B8,
+}
+
+#[bitfield(bits = 8, bytes = 1)]
+pub struct Twice {
+    a: B8,
+}
+
+fn main() {}
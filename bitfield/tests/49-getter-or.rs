@@ -0,0 +1,38 @@
+// Fields with a user-defined Specifier also get `get_x_or(default)`, which
+// returns the default instead of panicking when the stored bits are not a
+// valid value, e.g. an enum discriminant that doesn't exist.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Telemetry {
+    state: State,
+    level: B5,
+}
+
+#[bitfield(repr = u8)]
+pub struct Small {
+    state: State,
+    level: B5,
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+#[bits = 3]
+pub enum State {
+    Idle = 0,
+    Busy = 1,
+    Error = 5,
+}
+
+fn main() {
+    let mut telemetry = Telemetry::new();
+    telemetry.set_state(State::Busy);
+    assert_eq!(telemetry.get_state_or(State::Error), State::Busy);
+
+    let small = Small::from(0b01010_111);
+    assert_eq!(small.get_state_or(State::Error), State::Error);
+    assert_eq!(small.get_level(), 0b01010);
+
+    let small = Small::from(0b00000_101);
+    assert_eq!(small.get_state_or(State::Idle), State::Error);
+}
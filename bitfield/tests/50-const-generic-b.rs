@@ -0,0 +1,33 @@
+// `B<N>` is a field of N bits whose width can be computed, so layouts can be
+// derived from constants instead of picking one of the `B1`..`B64` aliases.
+
+use bitfield::*;
+
+const ADDR_BITS: usize = 20;
+const TAG_BITS: usize = 32 - ADDR_BITS - 2;
+
+#[bitfield]
+pub struct Entry {
+    valid: bool,
+    dirty: B<1>,
+    addr: B<{ ADDR_BITS }>,
+    tag: B<TAG_BITS>,
+}
+
+fn main() {
+    assert_eq!(<B<{ ADDR_BITS }> as Specifier>::BITS, 20);
+    assert_eq!(Entry::TAG_OFFSET, 22);
+    assert_eq!(std::mem::size_of::<Entry>(), 4);
+
+    let mut entry = Entry::new();
+    entry.set_valid(true);
+    entry.set_dirty(1);
+    entry.set_addr(0xfffff);
+    entry.set_tag(0x3ff);
+    assert!(entry.get_valid());
+    assert_eq!(entry.get_dirty(), 1);
+    let addr: u32 = entry.get_addr();
+    assert_eq!(addr, 0xfffff);
+    let tag: u16 = entry.get_tag();
+    assert_eq!(tag, 0x3ff);
+}
@@ -0,0 +1,44 @@
+// `Bytes<M>` fields hold M opaque bytes, wider than 64 bits if needed, and
+// are read and written as `[u8; M]`. Fields after them keep their offsets,
+// even when the bytes don't start on a byte boundary.
+
+use bitfield::*;
+
+#[bitfield(view)]
+pub struct Frame {
+    kind: B4,
+    mac: Bytes<6>,
+    key: Bytes<16>,
+    flags: B4,
+}
+
+fn main() {
+    assert_eq!(Frame::KEY_OFFSET, 52);
+    assert_eq!(Frame::FLAGS_OFFSET, 180);
+    assert_eq!(std::mem::size_of::<Frame>(), 23);
+
+    let mac = [0x00, 0x1b, 0x63, 0x84, 0x45, 0xe6];
+    let key = *b"0123456789abcdef";
+
+    let mut frame = Frame::new();
+    frame.set_kind(0xa);
+    frame.set_mac(mac);
+    frame.set_key(key);
+    frame.set_flags(0x5);
+    assert_eq!(frame.get_kind(), 0xa);
+    assert_eq!(frame.get_mac(), mac);
+    assert_eq!(frame.get_key(), key);
+    assert_eq!(frame.get_flags(), 0x5);
+
+    frame.update(|w| {
+        w.mac([0xff; 6]);
+    });
+    assert_eq!(frame.get_mac(), [0xff; 6]);
+    assert_eq!(frame.get_key(), key);
+    assert_eq!(frame.get_kind(), 0xa);
+
+    assert!(frame
+        .dump()
+        .to_string()
+        .contains("key   [179:52] = [30 31 32 33 34 35 36 37 38 39 61 62 63 64 65 66]"));
+}
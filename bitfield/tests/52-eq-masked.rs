@@ -0,0 +1,39 @@
+// `eq_masked` compares two values on their defined fields only. Fields marked
+// #[reserved] and bits beyond the last field of an integer repr are ignored,
+// so junk in padding doesn't make equal registers look different.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Status {
+    ready: bool,
+    #[reserved]
+    pad: B3,
+    code: B4,
+}
+
+#[bitfield(repr = u16)]
+pub struct Short {
+    a: B4,
+    #[reserved]
+    rsvd: B4,
+}
+
+fn main() {
+    let mut a = Status::new();
+    let mut b = Status::new();
+    a.set_ready(true);
+    b.set_ready(true);
+    a.set_code(9);
+    b.set_code(9);
+    b.set_pad(0b101);
+    assert!(a.eq_masked(&b));
+    b.set_code(8);
+    assert!(!a.eq_masked(&b));
+
+    let a = Short::from(0x0003);
+    let b = Short::from(0xfff3);
+    assert!(a.eq_masked(&b));
+    let c = Short::from(0x0004);
+    assert!(!a.eq_masked(&c));
+}
@@ -0,0 +1,46 @@
+// #[bitfield(diff)] adds `diff`, which compares two values field by field and
+// reports the old and new value of every field that changed.
+
+use bitfield::*;
+
+#[bitfield(diff)]
+pub struct Control {
+    pub enable: bool,
+    pub mode: Mode,
+    pub divider: B5,
+}
+
+#[bitfield(repr = u16, diff)]
+pub struct Word {
+    pub low: B8,
+    pub high: B8,
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+pub enum Mode {
+    Slow = 0,
+    Normal = 1,
+    Fast = 2,
+    Turbo = 3,
+}
+
+fn main() {
+    let mut before = Control::new();
+    before.set_mode(Mode::Normal);
+    before.set_divider(3);
+    let mut after = Control::new();
+    after.set_enable(true);
+    after.set_mode(Mode::Turbo);
+    after.set_divider(3);
+
+    let diff = before.diff(&after);
+    assert!(!diff.is_empty());
+    assert_eq!(diff.enable, Some((false, true)));
+    assert_eq!(diff.mode, Some((Mode::Normal, Mode::Turbo)));
+    assert_eq!(diff.divider, None);
+    assert!(before.diff(&before).is_empty());
+
+    let diff = Word::from(0x1234).diff(&Word::from(0x1235));
+    assert_eq!(diff.low, Some((0x34, 0x35)));
+    assert_eq!(diff.high, None);
+}
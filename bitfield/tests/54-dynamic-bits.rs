@@ -0,0 +1,58 @@
+// `read_bits`/`write_bits` read and write an arbitrary range given at
+// runtime, e.g. by a register-poking tool. Ranges outside the struct, or wider
+// than 64 bits, panic; `try_read_bits`/`try_write_bits` return a `RangeError`
+// instead. Their names can't clash with the `get_`/`set_` accessors of a
+// field, even one named `bits`.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Control {
+    enable: bool,
+    mode: B3,
+    divider: B12,
+}
+
+#[bitfield(repr = u32)]
+pub struct Word {
+    low: B16,
+    high: B16,
+}
+
+#[bitfield]
+pub struct Reg {
+    bits: B4,
+    rest: B4,
+}
+
+fn main() {
+    let mut control = Control::new();
+    control.set_mode(0b101);
+    control.set_divider(0xabc);
+    assert_eq!(control.read_bits(1, 3), 0b101);
+    assert_eq!(control.read_bits(4, 12), 0xabc);
+
+    control.write_bits(0, 4, 0b0111);
+    assert!(control.get_enable());
+    assert_eq!(control.get_mode(), 0b011);
+    assert_eq!(control.get_divider(), 0xabc);
+
+    let mut word = Word::new();
+    word.write_bits(8, 16, 0xffff);
+    assert_eq!(word.get_low(), 0xff00);
+    assert_eq!(word.get_high(), 0x00ff);
+    assert_eq!(word.read_bits(4, 24), 0x0ffff0);
+
+    assert_eq!(control.try_read_bits(4, 12), Ok(0xabc));
+    assert_eq!(control.try_read_bits(8, 9), Err(RangeError { offset: 8, len: 9, size: 16 }));
+    assert!(word.try_write_bits(0, 33, 0).is_err());
+    assert_eq!(word.try_write_bits(16, 16, 0x1234), Ok(()));
+    assert_eq!(word.get_high(), 0x1234);
+
+    let mut reg = Reg::new();
+    reg.set_bits(0b1010);
+    reg.write_bits(4, 4, 0b0110);
+    assert_eq!(reg.get_bits(), 0b1010);
+    assert_eq!(reg.get_rest(), 0b0110);
+    assert_eq!(reg.read_bits(0, 8), 0x6a);
+}
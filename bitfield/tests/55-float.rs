@@ -0,0 +1,31 @@
+// `f32` and `f64` fields take 32 and 64 bits holding the IEEE-754 bit
+// pattern, at any bit offset.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Sample {
+    channel: B4,
+    temperature: f32,
+    valid: bool,
+    timestamp: f64,
+    reserved: B3,
+}
+
+fn main() {
+    let mut sample = Sample::new();
+    sample.set_channel(3);
+    sample.set_temperature(-12.5);
+    sample.set_valid(true);
+    sample.set_timestamp(1.0e9 + 0.25);
+
+    assert_eq!(sample.get_channel(), 3);
+    assert_eq!(sample.get_temperature(), -12.5);
+    assert!(sample.get_valid());
+    assert_eq!(sample.get_timestamp(), 1.0e9 + 0.25);
+    assert_eq!(Sample::TIMESTAMP_OFFSET, 37);
+
+    sample.set_temperature(f32::NAN);
+    assert!(sample.get_temperature().is_nan());
+    assert_eq!(sample.get_timestamp(), 1.0e9 + 0.25);
+}
@@ -0,0 +1,41 @@
+// `from_slice` and `TryFrom<&[u8]>` copy a byte slice of exactly the right
+// length into the bitfield, and report a `LengthError` otherwise.
+
+use bitfield::*;
+use std::convert::TryFrom;
+
+#[bitfield]
+pub struct Header {
+    version: B4,
+    ihl: B4,
+    tos: B8,
+    length: B16,
+}
+
+#[bitfield(repr = u16)]
+pub struct Flags {
+    a: B4,
+    b: B12,
+}
+
+fn main() {
+    let packet = [0x45, 0x00, 0x3c, 0x00, 0xde, 0xad];
+
+    let header = Header::from_slice(&packet[..4]).unwrap();
+    assert_eq!(header.get_version(), 5);
+    assert_eq!(header.get_ihl(), 4);
+    assert_eq!(header.get_length(), 0x3c);
+
+    let header = Header::try_from(&packet[..4]).unwrap();
+    assert_eq!(header.get_version(), 5);
+
+    let err = Header::try_from(&packet[..]).err().unwrap();
+    assert_eq!(err, LengthError { expected: 4, actual: 6 });
+    assert_eq!(err.to_string(), "expected 4 bytes, got 6");
+    assert!(Header::from_slice(&[]).is_err());
+
+    let flags = Flags::try_from(&[0x21, 0x43][..]).unwrap();
+    assert_eq!(flags.get_a(), 1);
+    assert_eq!(flags.get_b(), 0x432);
+    assert_eq!(Flags::from_slice(&[0]).err(), Some(LengthError { expected: 2, actual: 1 }));
+}
@@ -0,0 +1,78 @@
+// With `layout = "c"` fields are placed the way GCC and Clang lay out C
+// bitfields on the SysV ABI: each field's `Specifier::Type` is its storage
+// unit, a field that would straddle a unit boundary starts the next unit, and
+// the total is rounded up to the widest unit. The bytes below match
+//
+//     struct Header {
+//         uint8_t a : 3;
+//         uint8_t b : 6;
+//         uint16_t c : 10;
+//         uint32_t d : 20;
+//         bool e : 1;
+//     };
+//
+// `B1` to `B8` are `uint8_t` units, `B9` to `B16` `uint16_t` ones and so on.
+// To declare another unit, spell the field `Bn<Unit, N>`: the header form
+// `unsigned a : 3; unsigned b : 6;` shares one 32-bit unit, which is
+// `Bn<u32, 3>` and `Bn<u32, 6>`.
+
+use bitfield::*;
+
+#[bitfield(layout = "c")]
+pub struct Header {
+    a: B3,
+    b: B6,
+    c: B10,
+    d: B20,
+    e: bool,
+}
+
+#[bitfield(layout = "c")]
+pub struct Flags {
+    a: Bn<u32, 3>,
+    b: Bn<u32, 6>,
+}
+
+#[bitfield(layout = "c", repr = u16)]
+pub struct Pair {
+    x: B7,
+    y: B2,
+}
+
+fn main() {
+    assert_eq!(std::mem::size_of::<Header>(), 8);
+    assert_eq!(Header::B_OFFSET, 8);
+    assert_eq!(Header::C_OFFSET, 16);
+    assert_eq!(Header::D_OFFSET, 32);
+    assert_eq!(Header::E_OFFSET, 52);
+
+    let mut header = Header::new();
+    header.set_a(5);
+    header.set_b(33);
+    header.set_c(700);
+    header.set_d(0xabcde);
+    header.set_e(true);
+    assert_eq!(format!("{:x}", header), "001abcde02bc2105");
+
+    let header = Header::from_slice(&[0x05, 0x21, 0xbc, 0x02, 0xde, 0xbc, 0x1a, 0x00]).unwrap();
+    assert_eq!(header.get_a(), 5);
+    assert_eq!(header.get_b(), 33);
+    assert_eq!(header.get_c(), 700);
+    assert_eq!(header.get_d(), 0xabcde);
+    assert!(header.get_e());
+
+    assert_eq!(std::mem::size_of::<Flags>(), 4);
+    assert_eq!(Flags::B_OFFSET, 3);
+
+    let mut flags = Flags::new();
+    flags.set_a(5);
+    flags.set_b(33);
+    assert_eq!(flags.get_a(), 5);
+    assert_eq!(flags.get_b(), 33);
+    assert_eq!(format!("{:x}", flags), "0000010d");
+
+    let mut pair = Pair::new();
+    pair.set_x(0x7f);
+    pair.set_y(2);
+    assert_eq!(u16::from(pair), 0x027f);
+}
@@ -0,0 +1,27 @@
+// In `layout = "c"` a field cannot be wider than its storage unit, the same
+// as `uint8_t x : 9;` is rejected by a C compiler.
+
+use bitfield::*;
+
+pub struct Nine;
+
+impl Specifier for Nine {
+    const BITS: usize = 9;
+    type Type = u8;
+
+    fn from(v: u64) -> u8 {
+        v as u8
+    }
+
+    fn to(v: u8) -> u64 {
+        v as u64
+    }
+}
+
+#[bitfield(layout = "c")]
+pub struct Packet {
+    wide: Nine,
+    rest: B7,
+}
+
+fn main() {}
@@ -0,0 +1,31 @@
+// `masks` generates `FooMasks` with each field's mask in place and a helper
+// shifting a raw value into it, for code that talks to registers through the
+// plain integer.
+
+use bitfield::*;
+
+#[bitfield(repr = u32, masks)]
+pub struct Descriptor {
+    length: B16,
+    owner: bool,
+    r#type: B3,
+    reserved: B11,
+    enable: bool,
+}
+
+fn main() {
+    assert_eq!(DescriptorMasks::LENGTH, 0xffff);
+    assert_eq!(DescriptorMasks::OWNER, 1 << 16);
+    assert_eq!(DescriptorMasks::TYPE, 0b111 << 17);
+    assert_eq!(DescriptorMasks::ENABLE, 1 << 31);
+
+    const RAW: u32 = DescriptorMasks::enable(1) | DescriptorMasks::r#type(5) | DescriptorMasks::length(0x1234);
+    assert_eq!(DescriptorMasks::owner(3), 1 << 16);
+
+    let descriptor = Descriptor::from(RAW);
+    assert!(descriptor.get_enable());
+    assert!(!descriptor.get_owner());
+    assert_eq!(descriptor.get_type(), 5);
+    assert_eq!(descriptor.get_length(), 0x1234);
+    assert_eq!(u32::from(descriptor) & DescriptorMasks::TYPE, 5 << 17);
+}
@@ -0,0 +1,55 @@
+// The expansions name everything through absolute `::core` and `::bitfield`
+// paths, so they compile in a module without the prelude.
+
+#![no_implicit_prelude]
+
+use ::bitfield::*;
+
+#[derive(BitfieldSpecifier, Clone, Copy, PartialEq, Debug)]
+pub enum Mode {
+    Off,
+    Slow,
+    Fast,
+    Max,
+}
+
+#[bitfield(unpacked, view, diff)]
+pub struct Packed {
+    mode: Mode,
+    #[reserved]
+    pad: B2,
+    value: B11,
+    flag: bool,
+    float: f32,
+    #[cfg(any())]
+    gone: B8,
+}
+
+#[bitfield(repr = u16, atomic, masks, layout = "c")]
+pub struct Register {
+    low: B4,
+    high: B4,
+    #[reserved]
+    rest: B8,
+}
+
+fn main() {
+    let mut packed = Packed::new();
+    packed.set_mode(Mode::Fast);
+    packed.set_value(1000);
+    packed.update(|w| {
+        w.flag(true);
+    });
+    ::std::assert_eq!(packed.get_mode(), Mode::Fast);
+    ::std::assert_eq!(packed.get_mode_or(Mode::Off), Mode::Fast);
+    ::std::assert!(!packed.eq_masked(&Packed::new()));
+    ::std::assert!(Packed::from_slice(&[0; 6]).is_ok());
+    ::std::assert!(packed.diff(&Packed::new()).value.is_some());
+    ::std::assert!(PackedView::new(&[0; 6]).is_some());
+    let _ = packed.unpack();
+    let _ = ::std::format!("{} {:x} {:b}", packed.dump(), packed, packed);
+
+    let register = AtomicRegister::new(::core::convert::From::from(RegisterMasks::high(3)));
+    ::std::assert_eq!(register.load(::core::sync::atomic::Ordering::Relaxed).get_high(), 3);
+    ::std::assert!(<Register as ::core::convert::TryFrom<&[u8]>>::try_from(&[0u8; 2][..]).is_ok());
+}
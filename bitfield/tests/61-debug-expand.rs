@@ -0,0 +1,16 @@
+// `debug_expand` prints the expansion to stderr while compiling, without
+// changing what is generated.
+
+use bitfield::*;
+
+#[bitfield(repr = u8, debug_expand)]
+pub struct Tiny {
+    low: B4,
+    high: B4,
+}
+
+fn main() {
+    let mut tiny = Tiny::new();
+    tiny.set_high(0xa);
+    assert_eq!(u8::from(tiny), 0xa0);
+}
@@ -0,0 +1,24 @@
+// Options are parsed by the same attribute layer as the other macros: a flag
+// takes no value and an option may only be given once.
+
+use bitfield::*;
+
+#[bitfield(view = true)]
+pub struct Flag {
+    a: B8,
+}
+
+#[bitfield(repr = u8, repr = u16)]
+pub struct Twice {
+    a: B8,
+}
+
+#[bitfield(derive(Clone), derive(Copy))]
+pub struct Derives {
+    a: B8,
+}
+
+fn main() {
+    let d = Derives::new();
+    let _ = (d, d);
+}
@@ -0,0 +1,58 @@
+// `bitfield::macros::bitfield!` takes a whole struct, with its options in a
+// `#[bitfield(...)]` attribute on the struct, so that layouts can be stamped
+// out from `macro_rules!` and `seq!` bodies.
+
+use bitfield::*;
+use seq::seq;
+
+macro_rules! register {
+    ($name:ident { $($field:ident: $ty:ty,)* }) => {
+        bitfield::macros::bitfield! {
+            #[derive(Clone, Copy)]
+            #[bitfield(repr = u8)]
+            pub struct $name {
+                $($field: $ty,)*
+            }
+        }
+    };
+}
+
+register!(Status {
+    ready: bool,
+    error: bool,
+    count: B6,
+});
+
+seq!(N in 0..3 {
+    bitfield::macros::bitfield! {
+        pub struct Channel#N {
+            gain: B4,
+            mode: B4,
+        }
+    }
+});
+
+// A bare `#[bitfield]` is allowed and adds nothing.
+bitfield::macros::bitfield! {
+    #[bitfield]
+    #[bitfield(bits = 16)]
+    pub struct Word {
+        lo: B8,
+        hi: B8,
+    }
+}
+
+fn main() {
+    let mut status = Status::new();
+    status.set_count(42);
+    let copy = status;
+    assert_eq!(u8::from(copy), 42 << 2);
+
+    let mut channel = Channel2::new();
+    channel.set_gain(3);
+    assert_eq!(channel.get_gain(), 3);
+    assert_eq!(std::mem::size_of::<Channel0>(), 1);
+    assert_eq!(std::mem::size_of::<Channel1>(), 1);
+
+    assert_eq!(std::mem::size_of::<Word>(), 2);
+}
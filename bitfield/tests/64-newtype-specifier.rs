@@ -0,0 +1,40 @@
+// A newtype over an unsigned integer can derive BitfieldSpecifier too. It
+// takes the full width of the integer unless `#[bits = N]` narrows it, and a
+// field of that type reads and writes the newtype itself.
+//
+// Storing a value that doesn't fit in the narrowed width panics, the same way
+// as for a `Bn`; `checked_from` reports such bit patterns as `None`.
+
+use bitfield::*;
+
+#[derive(BitfieldSpecifier, Clone, Copy, Debug, PartialEq)]
+#[bits = 12]
+pub struct DeviceId(u16);
+
+#[derive(BitfieldSpecifier, Clone, Copy, Debug, PartialEq)]
+pub struct Vendor(u8);
+
+#[bitfield]
+pub struct Descriptor {
+    id: DeviceId,
+    #[bits = 4]
+    rev: B4,
+    vendor: Vendor,
+}
+
+fn main() {
+    assert_eq!(<DeviceId as Specifier>::BITS, 12);
+    assert_eq!(<Vendor as Specifier>::BITS, 8);
+
+    let mut desc = Descriptor::new();
+    desc.set_id(DeviceId(0xabc));
+    desc.set_rev(5);
+    desc.set_vendor(Vendor(0x42));
+    assert_eq!(desc.get_id(), DeviceId(0xabc));
+    assert_eq!(desc.get_rev(), 5);
+    assert_eq!(desc.get_vendor(), Vendor(0x42));
+    assert_eq!(desc.read_bits(0, 24), 0x42_5abc);
+
+    assert_eq!(<DeviceId as Specifier>::checked_from(0xfff), Some(DeviceId(0xfff)));
+    assert_eq!(<DeviceId as Specifier>::checked_from(0x1000), None);
+}
@@ -0,0 +1,18 @@
+// Only newtypes over `u8`, `u16`, `u32` or `u64` are supported, and
+// `#[bits = N]` may not exceed the width of the integer.
+
+use bitfield::*;
+
+#[derive(BitfieldSpecifier)]
+pub struct Signed(i16);
+
+#[derive(BitfieldSpecifier)]
+pub struct Named {
+    raw: u16,
+}
+
+#[derive(BitfieldSpecifier)]
+#[bits = 12]
+pub struct Narrow(u8);
+
+fn main() {}
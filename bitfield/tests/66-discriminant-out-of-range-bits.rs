@@ -0,0 +1,15 @@
+// With #[bits = N] the variants don't have to cover every bit pattern, but
+// each discriminant still has to fit in N bits. One that doesn't is reported
+// on the offending variant.
+
+use bitfield::*;
+
+#[derive(BitfieldSpecifier)]
+#[bits = 2]
+pub enum Sparse {
+    A = 0,
+    B = 2,
+    C = 5,
+}
+
+fn main() {}
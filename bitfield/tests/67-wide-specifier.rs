@@ -0,0 +1,25 @@
+// `Specifier::get` and `set` only know how to move up to 64 bits. A wider
+// specifier has to override them, like `Bytes` does, and using the defaults
+// is a compile error rather than a panic.
+
+use bitfield::*;
+
+pub struct Wide;
+
+impl Specifier for Wide {
+    const BITS: usize = 72;
+    type Type = u64;
+
+    fn from(v: u64) -> u64 {
+        v
+    }
+
+    fn to(v: u64) -> u64 {
+        v
+    }
+}
+
+fn main() {
+    let data = [0u8; 9];
+    let _ = Wide::get(0, &data);
+}
@@ -9,8 +9,72 @@ fn tests() {
     t.pass("tests/06-enums.rs");
     t.pass("tests/07-optional-discriminant.rs");
     t.compile_fail("tests/08-non-power-of-two.rs");
-    //t.compile_fail("tests/09-variant-out-of-range.rs");
+    t.compile_fail("tests/09-variant-out-of-range.rs");
     //t.pass("tests/10-bits-attribute.rs");
     //t.compile_fail("tests/11-bits-attribute-wrong.rs");
     t.pass("tests/12-accessors-edge.rs");
+    t.pass("tests/13-repr-int.rs");
+    t.compile_fail("tests/14-repr-int-overflow.rs");
+    t.pass("tests/15-accessor-visibility.rs");
+    t.compile_fail("tests/16-accessor-visibility-private.rs");
+    t.pass("tests/17-const-accessors.rs");
+    t.pass("tests/18-clear.rs");
+    t.pass("tests/19-modify.rs");
+    t.pass("tests/20-atomic.rs");
+    t.compile_fail("tests/21-atomic-without-repr.rs");
+    t.pass("tests/24-derives.rs");
+    t.pass("tests/25-binary-hex.rs");
+    t.pass("tests/26-layout-consts.rs");
+    t.pass("tests/27-field-table.rs");
+    t.pass("tests/28-implicit-discriminants.rs");
+    t.pass("tests/29-enum-bits.rs");
+    t.compile_fail("tests/30-sparse-discriminants.rs");
+    t.pass("tests/31-char.rs");
+    t.pass("tests/32-custom-specifier.rs");
+    t.pass("tests/33-unpacked.rs");
+    t.pass("tests/34-view.rs");
+    t.pass("tests/35-view-mut.rs");
+    t.pass("tests/36-update.rs");
+    t.pass("tests/37-byte-aligned.rs");
+    t.pass("tests/38-no-std.rs");
+    t.pass("tests/39-field-attrs.rs");
+    t.compile_fail("tests/40-field-deprecated.rs");
+    t.pass("tests/41-dump.rs");
+    t.pass("tests/43-cfg-fields.rs");
+    t.compile_fail("tests/44-no-fields.rs");
+    t.compile_fail("tests/45-field-width.rs");
+    t.compile_fail("tests/46-not-specifier.rs");
+    t.pass("tests/47-declared-size.rs");
+    t.compile_fail("tests/48-declared-size-mismatch.rs");
+    t.pass("tests/49-getter-or.rs");
+    t.pass("tests/50-const-generic-b.rs");
+    t.pass("tests/51-bytes.rs");
+    t.pass("tests/52-eq-masked.rs");
+    t.pass("tests/53-diff.rs");
+    t.pass("tests/54-dynamic-bits.rs");
+    t.pass("tests/55-float.rs");
+    t.pass("tests/56-try-from-slice.rs");
+    t.pass("tests/57-c-layout.rs");
+    t.compile_fail("tests/58-c-layout-unit.rs");
+    t.pass("tests/59-masks.rs");
+    t.pass("tests/60-no-implicit-prelude.rs");
+    t.pass("tests/61-debug-expand.rs");
+    t.compile_fail("tests/62-bad-options.rs");
+    t.pass("tests/63-function-like.rs");
+    t.pass("tests/64-newtype-specifier.rs");
+    t.compile_fail("tests/65-newtype-specifier-wrong.rs");
+    t.compile_fail("tests/66-discriminant-out-of-range-bits.rs");
+    t.compile_fail("tests/67-wide-specifier.rs");
+
+    if cfg!(feature = "arbitrary") {
+        t.pass("tests/42-arbitrary.rs");
+    }
+
+    if cfg!(feature = "defmt") {
+        t.pass("tests/23-defmt.rs");
+    }
+
+    if cfg!(feature = "serde") {
+        t.pass("tests/22-serde.rs");
+    }
 }
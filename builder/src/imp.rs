@@ -1,203 +1,2028 @@
-use proc_macro2::TokenStream;
-use quote::{format_ident, quote};
-use syn::{Attribute, Data, DeriveInput, GenericArgument, Ident, LitStr, Path, PathArguments, PathSegment, Token, Type, TypePath};
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use attrs::{Args, Value};
+use syn::{Attribute, Data, DeriveInput, Expr, FnArg, GenericArgument, GenericParam, Ident, ItemFn, Lifetime, LifetimeDef, Lit, LitStr, ParenthesizedGenericArguments, Pat, PatIdent, Path, PathArguments, PathSegment, ReturnType, Token, Type, TypeBareFn, TypeParam, TypePath, TypeReference};
+use syn::ext::IdentExt;
+use syn::parse::Parse;
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::parse::{Parse, ParseStream};
+use syn::visit_mut::{self, VisitMut};
+
+// The keys of `#[builder(...)]` on a field and on the struct, for suggestions.
+const FIELD_KEYS: &[&str] = &["each", "default", "into", "field", "optional", "wrap", "setter", "no_strip_option", "sensitive", "alias", "as_ref", "by_name"];
+const CONTAINER_KEYS: &[&str] = &["into", "owned", "typestate", "validate", "error", "derive", "setter_prefix", "serde", "module", "const", "field_enum", "debug_expand"];
 
 #[derive(Default)]
 struct Attrs {
-    each: Option<LitStr>,
+    each: Option<Ident>,
+    // `#[builder(default)]` or `#[builder(default = "expr")]`: what an unset
+    // field builds as, spanned on the attribute.
+    default: Option<(Ident, TokenStream)>,
+    // `#[builder(into)]`: the setter takes `impl Into<T>`.
+    into: Option<Ident>,
+    // `#[builder(field(ty = "Type", build = "expr"))]`: the builder holds a
+    // `Type`, starting from its default, and build() evaluates `expr` for the
+    // field. `expr` can read the builder as `self`.
+    field: Option<(Ident, Type, Expr)>,
+    // `#[builder(optional)]`: the field's type is an `Option` by another
+    // name, like an alias `Maybe<T>`.
+    optional: Option<Ident>,
+    // `#[builder(wrap)]`: the setter takes what the field's `Box`, `Rc` or
+    // `Arc` points to.
+    wrap: Option<Ident>,
+    // `#[builder(no_strip_option)]`: an `Option<T>` field is required like
+    // any other, and its setter takes the `Option<T>`.
+    no_strip_option: Option<Ident>,
+    // `#[builder(as_ref)]`: the setter takes `impl AsRef<str>` for a
+    // `String` and the like, see `borrowed`.
+    as_ref: Option<Ident>,
+    // `#[builder(sensitive)]`: the builder's Debug shows `"***"` instead.
+    sensitive: Option<Ident>,
+    // `#[builder(alias = "name")]`, repeatable: more names for the setter.
+    aliases: Vec<Ident>,
+    // `#[builder(by_name = false)]`: `set_by_name()` leaves the field out,
+    // so that its type needn't be `FromStr`.
+    by_name: Option<bool>,
+    // `#[builder(setter(custom))]`: no setters are generated; the user
+    // writes their own against the builder's field.
+    custom_setter: Option<Ident>,
+    // Warnings about deprecated spellings, see `attrs::warning`.
+    warnings: TokenStream,
 }
 
 impl Attrs {
-    fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
-        for attr in attrs {
-            if attr.path.is_ident("builder") {
-                let attr = match attr.parse_args::<Self>() {
-                    Ok(attr) => attr,
-                    Err(err) => return Err(syn::Error::new_spanned(attr.parse_meta()?, err)),
+    fn from_attrs(ident: &Ident, attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut each = None;
+        let mut default = None;
+        let mut into = None;
+        let mut field = None;
+        let mut optional = None;
+        let mut wrap = None;
+        let mut no_strip_option = None;
+        let mut sensitive = None;
+        let mut as_ref = None;
+        let mut aliases = Vec::new();
+        let mut by_name = None;
+        let mut custom_setter = None;
+        let mut warnings = TokenStream::new();
+        for attr in attrs.iter().filter(|attr| attr.path.is_ident("builder")) {
+            let (path, tokens) = (&attr.path, &attr.tokens);
+            for arg in Args::from_attrs("builder", std::slice::from_ref(attr))? {
+                if arg.key == "default" {
+                    let expr = match &arg.value {
+                        Value::Flag => quote_spanned!(arg.key.span()=> ::core::default::Default::default()),
+                        _ => parse_lit::<Expr>(arg.lit_str()?)?.into_token_stream(),
+                    };
+                    attrs::set(&mut default, &arg, (arg.key.clone(), expr))?;
+                    continue;
+                }
+                if arg.key == "into" {
+                    arg.flag()?;
+                    attrs::set(&mut into, &arg, arg.key.clone())?;
+                    continue;
+                }
+                if arg.key == "setter" {
+                    for inner in arg.list(Args::parse)? {
+                        if inner.key != "custom" {
+                            let error = syn::Error::new_spanned(&inner, "expected `custom`");
+                            return Err(attrs::unknown(&inner, &["custom"], error));
+                        }
+                        inner.flag()?;
+                        attrs::set(&mut custom_setter, &inner, inner.key.clone())?;
+                    }
+                    continue;
+                }
+                if arg.key == "wrap" {
+                    arg.flag()?;
+                    attrs::set(&mut wrap, &arg, arg.key.clone())?;
+                    continue;
+                }
+                if arg.key == "alias" {
+                    aliases.push(parse_lit::<Ident>(arg.lit_str()?)?);
+                    continue;
+                }
+                if arg.key == "by_name" {
+                    let value = match &arg.value {
+                        Value::Lit(Lit::Bool(lit)) => lit.value,
+                        _ => return Err(syn::Error::new_spanned(&arg, "expected `by_name = false`")),
+                    };
+                    attrs::set(&mut by_name, &arg, value)?;
+                    continue;
+                }
+                if arg.key == "as_ref" {
+                    arg.flag()?;
+                    attrs::set(&mut as_ref, &arg, arg.key.clone())?;
+                    continue;
+                }
+                if arg.key == "sensitive" {
+                    arg.flag()?;
+                    attrs::set(&mut sensitive, &arg, arg.key.clone())?;
+                    continue;
+                }
+                if arg.key == "no_strip_option" {
+                    arg.flag()?;
+                    attrs::set(&mut no_strip_option, &arg, arg.key.clone())?;
+                    continue;
+                }
+                if arg.key == "optional" {
+                    arg.flag()?;
+                    attrs::set(&mut optional, &arg, arg.key.clone())?;
+                    continue;
+                }
+                if arg.key == "field" {
+                    let (mut ty, mut build) = (None, None);
+                    for inner in arg.list(Args::parse)? {
+                        if inner.key == "ty" {
+                            attrs::set(&mut ty, &inner, parse_lit::<Type>(inner.lit_str()?)?)?;
+                        } else if inner.key == "build" {
+                            attrs::set(&mut build, &inner, parse_lit::<Expr>(inner.lit_str()?)?)?;
+                        } else {
+                            let error = syn::Error::new_spanned(&inner, r#"expected `ty = "..."` or `build = "..."`"#);
+                            return Err(attrs::unknown(&inner, &["ty", "build"], error));
+                        }
+                    }
+                    match (ty, build) {
+                        (Some(ty), Some(build)) => attrs::set(&mut field, &arg, (arg.key.clone(), ty, build))?,
+                        _ => return Err(syn::Error::new_spanned(arg, r#"expected `field(ty = "...", build = "...")`"#)),
+                    }
+                    continue;
+                }
+                if arg.key != "each" {
+                    let error = syn::Error::new_spanned(quote! { #path #tokens }, r#"expected `builder(each = "...")`"#);
+                    return Err(attrs::unknown(&arg, FIELD_KEYS, error));
+                }
+                let name = match &arg.value {
+                    // Unquoted `each = name` is deprecated but still accepted.
+                    Value::Path(..) => {
+                        let name = arg.ident()?;
+                        warnings.extend(attrs::warning(name.span(), &format!(r#"`each = {0}` is deprecated, write `each = "{0}"`"#, name)));
+                        name.clone()
+                    }
+                    Value::Flag => each_name(ident),
+                    _ => format_ident!("{}", arg.lit_str()?.value()),
                 };
-                return Ok(attr); // TODO merge
+                attrs::set(&mut each, &arg, name)?;
             }
         }
-        Ok(Default::default())
+
+        if let (Some((default, _)), Some(..)) = (&default, &each) {
+            return Err(syn::Error::new_spanned(default, "`default` has no effect with `each`, which starts out empty"));
+        }
+        if let (Some(into), Some(..)) = (&into, &each) {
+            return Err(syn::Error::new_spanned(into, "`into` isn't supported with `each`, whose setter takes the collection's items"));
+        }
+        if let (Some((key, ..)), true) = (&field, each.is_some() || default.is_some()) {
+            return Err(syn::Error::new_spanned(key, "`field(...)` can't be combined with `each` or `default`"));
+        }
+        if let (Some(optional), true) = (&optional, each.is_some() || field.is_some()) {
+            return Err(syn::Error::new_spanned(optional, "`optional` can't be combined with `each` or `field(...)`"));
+        }
+        if let (Some(wrap), true) = (&wrap, each.is_some() || field.is_some()) {
+            return Err(syn::Error::new_spanned(wrap, "`wrap` can't be combined with `each` or `field(...)`"));
+        }
+        if let (Some(alias), Some(..)) = (aliases.first(), &custom_setter) {
+            return Err(syn::Error::new_spanned(alias, "`alias` has no effect with `setter(custom)`, which generates no setter"));
+        }
+        if let (Some(as_ref), true) = (&as_ref, each.is_some() || field.is_some() || into.is_some() || wrap.is_some()) {
+            return Err(syn::Error::new_spanned(as_ref, "`as_ref` can't be combined with `each`, `field(...)`, `into` or `wrap`"));
+        }
+        if let (Some(no_strip_option), true) = (&no_strip_option, each.is_some() || field.is_some() || optional.is_some()) {
+            return Err(syn::Error::new_spanned(no_strip_option, "`no_strip_option` can't be combined with `each`, `field(...)` or `optional`"));
+        }
+
+        Ok(Self {
+            each,
+            default,
+            into,
+            field,
+            optional,
+            wrap,
+            no_strip_option,
+            sensitive,
+            as_ref,
+            aliases,
+            by_name,
+            custom_setter,
+            warnings,
+        })
     }
 }
 
-impl Parse for Attrs {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        let mut each = None;
+// Parsing a string literal's contents gives them its span, so that errors in
+// them point there.
+fn parse_lit<T: Parse>(lit: &LitStr) -> syn::Result<T> {
+    lit.parse::<T>().map_err(|err| syn::Error::new(lit.span(), err))
+}
 
-        while input.peek(Ident) {
-            let name = input.parse::<Ident>()?;
-            if name == "each" {
-                input.parse::<Token![=]>()?;
-                each = Some(input.parse::<LitStr>()?);
-            } else {
-                return Err(syn::Error::new(input.span(), r#"expected `builder(each = "...")`"#));
+// `#[builder(...)]` on the struct itself.
+#[derive(Default)]
+struct ContainerAttrs {
+    // `#[builder(into)]` for every field but `each` ones.
+    into: bool,
+    // `#[builder(owned)]`: setters and `build()` take the builder by value.
+    owned: bool,
+    // `#[builder(typestate)]`, see `typestate`; implies `owned`.
+    typestate: bool,
+    // `#[builder(validate = "path", error = "Type")]`: `build()` passes the
+    // struct to `path`, a `fn(&Foo) -> Result<(), Type>`; `Type` defaults to
+    // `&'static str`, which needs no allocator.
+    validate: Option<(Path, Type)>,
+    // `#[builder(derive(Clone, Default))]`: traits the builder implements
+    // besides `Debug`. `Default` is written out as `Foo::builder()` so it
+    // needs no bound on the struct's parameters; the others are derived.
+    derive: Vec<Path>,
+    // `#[builder(setter_prefix = "with_")]`, put before the name of every
+    // setter, `each` ones included.
+    setter_prefix: String,
+    // `#[builder(serde)]`: the builder derives serde's `Serialize` and
+    // `Deserialize`, with absent fields left unset. The user's crate depends
+    // on serde, found at `::serde` unless given by
+    // `#[builder(serde(crate = "path"))]`, as for serde's own attribute.
+    serde: bool,
+    serde_crate: Option<(LitStr, Path)>,
+    // `#[builder(module)]`: see `module`.
+    module: bool,
+    // `#[builder(field_enum)]`: see `field_enum`.
+    field_enum: bool,
+    // `#[builder(const)]`: `Foo::builder()`, the setters and `build()` are
+    // `const fn`s; implies `owned`. Fields are then set as they are, the
+    // struct's fields can't need dropping and `validate` is a `const fn`.
+    const_fn: bool,
+    debug_expand: bool,
+    // Not an attribute: set by `#[builder]` on a function, see `function`.
+    call: Option<Call>,
+}
+
+impl ContainerAttrs {
+    fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut into = None;
+        let mut owned = None;
+        let mut typestate = None;
+        let mut debug_expand = None;
+        let mut serde = None;
+        let mut serde_crate = None;
+        let mut module = None;
+        let mut field_enum = None;
+        let mut const_fn = None;
+        let mut validate = None;
+        let mut error = None;
+        let mut derive = None;
+        let mut setter_prefix = None;
+        for arg in Args::from_attrs("builder", attrs)? {
+            if arg.key == "serde" {
+                if let Value::List(..) = arg.value {
+                    for inner in arg.list(Args::parse)? {
+                        if inner.key != "crate" {
+                            let error = syn::Error::new_spanned(&inner, r#"expected `crate = "..."`"#);
+                            return Err(attrs::unknown(&inner, &["crate"], error));
+                        }
+                        let lit = inner.lit_str()?;
+                        attrs::set(&mut serde_crate, &inner, (lit.clone(), parse_lit::<Path>(lit)?))?;
+                    }
+                } else {
+                    arg.flag()?;
+                }
+                attrs::set(&mut serde, &arg, arg.key.clone())?;
+                continue;
+            }
+            if arg.key == "field_enum" {
+                arg.flag()?;
+                attrs::set(&mut field_enum, &arg, arg.key.clone())?;
+                continue;
+            }
+            if arg.key == "const" {
+                arg.flag()?;
+                attrs::set(&mut const_fn, &arg, arg.key.clone())?;
+                continue;
+            }
+            if arg.key == "setter_prefix" {
+                let lit = arg.lit_str()?;
+                let prefix = lit.value();
+                if prefix.starts_with(|c: char| c.is_ascii_digit()) || !prefix.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                    return Err(syn::Error::new_spanned(lit, "expected the start of an identifier"));
+                }
+                attrs::set(&mut setter_prefix, &arg, prefix)?;
+                continue;
+            }
+            if arg.key == "derive" {
+                let paths = arg.list(Punctuated::<Path, Token![,]>::parse_terminated)?;
+                if let Some(debug) = paths.iter().find(|path| path.is_ident("Debug")) {
+                    return Err(syn::Error::new_spanned(debug, "the builder always implements `Debug`"));
+                }
+                attrs::set(&mut derive, &arg, paths.into_iter().collect())?;
+                continue;
             }
+            if arg.key == "validate" {
+                attrs::set(&mut validate, &arg, arg.lit_str()?.parse::<Path>()?)?;
+                continue;
+            }
+            if arg.key == "error" {
+                attrs::set(&mut error, &arg, (arg.key.clone(), arg.lit_str()?.parse::<Type>()?))?;
+                continue;
+            }
+            let slot = if arg.key == "into" {
+                &mut into
+            } else if arg.key == "owned" {
+                &mut owned
+            } else if arg.key == "typestate" {
+                &mut typestate
+            } else if arg.key == "module" {
+                &mut module
+            } else if arg.key == "debug_expand" {
+                &mut debug_expand
+            } else {
+                let error = syn::Error::new_spanned(&arg, r#"expected `builder(into)`, `builder(owned)`, `builder(typestate)`, `builder(validate = "...")`, `builder(derive(...))`, `builder(setter_prefix = "...")`, `builder(serde)`, `builder(module)`, `builder(const)`, `builder(field_enum)` or `builder(debug_expand)`"#);
+                return Err(attrs::unknown(&arg, CONTAINER_KEYS, error));
+            };
+            arg.flag()?;
+            attrs::set(slot, &arg, ())?;
+        }
+
+        if let (Some(serde), Some(())) = (&serde, typestate) {
+            return Err(syn::Error::new_spanned(serde, "`serde` isn't supported with `typestate`"));
+        }
+        if let (Some(field_enum), Some(())) = (&field_enum, typestate) {
+            return Err(syn::Error::new_spanned(field_enum, "`field_enum` isn't supported with `typestate`"));
+        }
+        if let (Some(key), true) = (&const_fn, typestate.is_some() || into.is_some()) {
+            return Err(syn::Error::new_spanned(key, "`const` can't be combined with `typestate` or `into`"));
         }
 
+        let validate = match (validate, error) {
+            (Some(validate), Some((_, error))) => Some((validate, error)),
+            (Some(validate), None) => Some((validate, syn::parse_quote!(&'static str))),
+            (None, Some((key, _))) => return Err(syn::Error::new_spanned(key, "`error` requires `validate`")),
+            (None, None) => None,
+        };
+
         Ok(Self {
-            each,
+            into: into.is_some(),
+            owned: owned.is_some() || const_fn.is_some(),
+            typestate: typestate.is_some(),
+            validate,
+            derive: derive.unwrap_or_default(),
+            setter_prefix: setter_prefix.unwrap_or_default(),
+            serde: serde.is_some(),
+            serde_crate,
+            module: module.is_some(),
+            field_enum: field_enum.is_some(),
+            const_fn: const_fn.is_some(),
+            debug_expand: debug_expand.is_some(),
+            call: None,
         })
     }
 }
 
+// `current_dir` as `CurrentDir`.
+fn camel_case(ident: &Ident) -> String {
+    let mut camel = String::new();
+    for part in ident.unraw().to_string().split('_') {
+        let mut chars = part.chars();
+        camel.extend(chars.next().map(|c| c.to_ascii_uppercase()));
+        camel.extend(chars);
+    }
+    camel
+}
+
+// The one-at-a-time setter of a bare `#[builder(each)]`: the field's name
+// with a trailing `ies` made `y` or a trailing `s` dropped, unless it ends in
+// `ss` or `us`. Otherwise, or if that isn't a plain name, `push_field`.
+fn each_name(field: &Ident) -> Ident {
+    let name = field.unraw().to_string();
+    let single = match name.strip_suffix("ies") {
+        Some(stem) => Some(format!("{}y", stem)),
+        None => name.strip_suffix('s').filter(|stem| !stem.ends_with(['s', 'u', '_'])).map(str::to_owned),
+    };
+    match single.filter(|single| syn::parse_str::<Ident>(single).is_ok()) {
+        Some(single) => Ident::new(&single, field.span()),
+        None => format_ident!("push_{}", name, span = field.span()),
+    }
+}
+
+fn prefixed(prefix: &str, ident: &Ident) -> Ident {
+    if prefix.is_empty() {
+        return ident.clone();
+    }
+    Ident::new(&format!("{}{}", prefix, ident.unraw()), ident.span())
+}
+
+// `Option<T>`, also spelled `option::Option<T>`, `std::option::Option<T>` or
+// `core::option::Option<T>`.
 fn infer_option(ty: &Type) -> (bool, &Type) {
-    if let Type::Path(TypePath { path: Path { segments, .. }, .. }) = ty {
-        if segments.len() == 1 {
-            let segment = segments.last().unwrap();
-            if let PathSegment { ident, arguments: PathArguments::AngleBracketed(args) } = segment {
-                if ident == "Option" && args.args.len() == 1 {
-                    if let GenericArgument::Type(ty) = args.args.first().unwrap() {
-                        return (true, ty);
-                    }
-                }
-            }
+    if let Type::Path(TypePath { qself: None, path: Path { segments, .. } }) = ty {
+        let names = segments.iter().map(|segment| segment.ident.to_string()).collect::<Vec<_>>();
+        let names = names.iter().map(String::as_str).collect::<Vec<_>>();
+        let qualified = matches!(names[..], ["Option"] | ["option", "Option"] | ["std" | "core", "option", "Option"]);
+        let bare = segments.iter().rev().skip(1).all(|segment| segment.arguments.is_empty());
+        if let (true, true, Some(inner)) = (qualified, bare, type_arg(ty)) {
+            return (true, inner);
         }
     }
 
     (false, ty)
 }
 
+// The `T` of `Box<T>`, `Rc<T>` or `Arc<T>`.
+fn pointee(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) if ["Box", "Rc", "Arc"].iter().any(|name| path.segments.last().unwrap().ident == name) => type_arg(ty),
+        _ => None,
+    }
+}
+
+// What a `String`, `PathBuf`, `OsString` or `Vec<T>` is made `From` a
+// reference to, for `#[builder(as_ref)]`: what it derefs to, so that `std`
+// needn't be named.
+fn borrowed(ty: &Type) -> TokenStream {
+    quote_spanned!(ty.span()=> <#ty as ::core::ops::Deref>::Target)
+}
+
+fn is_phantom(ty: &Type) -> bool {
+    matches!(ty, Type::Path(TypePath { qself: None, path }) if path.segments.last().unwrap().ident == "PhantomData")
+}
+
+// The `T` of a type written `Name<T>`, possibly with a path before `Name`.
+fn type_arg(ty: &Type) -> Option<&Type> {
+    if let Type::Path(TypePath { qself: None, path }) = ty {
+        if let Some(PathSegment { arguments: PathArguments::AngleBracketed(args), .. }) = path.segments.last() {
+            if let (1, Some(GenericArgument::Type(ty))) = (args.args.len(), args.args.first()) {
+                return Some(ty);
+            }
+        }
+    }
+    None
+}
+
+// Malformed `#[debug]` attributes are left for `CustomDebug` to report.
+fn debug_format(attrs: &[Attribute]) -> Option<LitStr> {
+    let args = Args::from_attrs("debug", attrs).ok()?;
+    args.into_iter().find(|arg| arg.key == "debug").and_then(|arg| arg.lit_str().ok().cloned())
+}
+
+fn debug_skip(attrs: &[Attribute]) -> bool {
+    Args::from_attrs("debug", attrs).is_ok_and(|args| args.into_iter().any(|arg| arg.key == "skip"))
+}
+
+// How setters take the builder: `&mut self`, or `mut self` with
+// `#[builder(owned)]`.
+struct Receiver {
+    param: TokenStream,
+    ret: TokenStream,
+    // `const` with `#[builder(const)]`, for the setters.
+    constness: Option<TokenStream>,
+}
+
+impl Receiver {
+    fn new(owned: bool) -> Self {
+        if owned {
+            Self { param: quote!(mut self), ret: quote!(Self), constness: None }
+        } else {
+            Self { param: quote!(&mut self), ret: quote!(&mut Self), constness: None }
+        }
+    }
+}
+
+// The item type of a std collection other than a map.
+fn collection_item(ty: &Type) -> Option<&Type> {
+    let name = match ty {
+        Type::Path(TypePath { qself: None, path }) => &path.segments.last()?.ident,
+        _ => return None,
+    };
+    let known = ["Vec", "VecDeque", "LinkedList", "HashSet", "BTreeSet", "BinaryHeap"];
+    known.iter().any(|known| name == known).then(|| type_arg(ty)).flatten()
+}
+
+// The key and value types of a `HashMap` or `BTreeMap`.
+fn map_types(ty: &Type) -> Option<(&Type, &Type)> {
+    let segment = match ty {
+        Type::Path(TypePath { qself: None, path }) => path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "HashMap" && segment.ident != "BTreeMap" {
+        return None;
+    }
+    let args = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => &args.args,
+        _ => return None,
+    };
+    match (args.iter().next(), args.iter().nth(1)) {
+        (Some(GenericArgument::Type(key)), Some(GenericArgument::Type(value))) => Some((key, value)),
+        _ => None,
+    }
+}
+
 struct TargetField<'a> {
     option: bool,
     ident: &'a Ident,
+    // The name of the whole-value setter; `each` is prefixed the same way.
+    setter: Ident,
     ty: &'a Type,
     each: Option<Ident>,
+    default: Option<TokenStream>,
+    into: bool,
+    // `#[debug = "..."]` of `CustomDebug`, honored by the builder's Debug.
+    debug: Option<LitStr>,
+    // `#[builder(sensitive)]`.
+    sensitive: bool,
+    // `#[debug(skip)]` of `CustomDebug`: left out of the builder's Debug too.
+    debug_skip: bool,
+    // The field's doc comments, repeated on its setters.
+    docs: Vec<&'a Attribute>,
+    // The field's `#[deprecated]`, repeated on its setters.
+    deprecated: Vec<&'a Attribute>,
+    // The field's `#[cfg]`s, repeated on everything generated for it.
+    cfgs: Vec<&'a Attribute>,
+    // `field(ty = "...", build = "...")`.
+    custom: Option<(Type, Expr)>,
+    // `#[builder(wrap)]`: what the field's pointer points to.
+    wrap: Option<&'a Type>,
+    // `#[builder(as_ref)]`: what the setter takes `impl AsRef` of.
+    as_ref: Option<TokenStream>,
+    // `#[builder(alias = "...")]`, prefixed like `setter`.
+    aliases: Vec<Ident>,
+    // `#[builder(setter(custom))]`.
+    custom_setter: Option<Ident>,
+    // Whether `set_by_name()` sets the field.
+    by_name: bool,
+    // A `PhantomData` field, held by the builder as a `field(...)` one that
+    // builds as `PhantomData`, with no setters.
+    phantom: bool,
+    warnings: TokenStream,
 }
 
 impl<'a> TargetField<'a> {
-    fn from(field: &'a syn::Field) -> syn::Result<Self> {
-        let attrs = Attrs::from_attrs(&field.attrs)?;
-        let (option, ty) = infer_option(&field.ty);
+    fn from(field: &'a syn::Field, container: &ContainerAttrs) -> syn::Result<Self> {
+        let attrs = Attrs::from_attrs(field.ident.as_ref().unwrap(), &field.attrs)?;
+        let (option, ty) = match (&attrs.field, &attrs.optional) {
+            (Some(..), _) => (false, &field.ty),
+            (None, Some(optional)) => match type_arg(&field.ty) {
+                Some(ty) => (true, ty),
+                None => return Err(syn::Error::new_spanned(optional, "`optional` needs a type written with one type argument, like `Maybe<T>`")),
+            },
+            (None, None) if attrs.no_strip_option.is_some() => (false, &field.ty),
+            (None, None) => infer_option(&field.ty),
+        };
+        if let (Some((default, _)), true) = (&attrs.default, option) {
+            return Err(syn::Error::new_spanned(default, "`default` has no effect on an `Option` field, which builds as `None`"));
+        }
+        let wrap = match &attrs.wrap {
+            Some(wrap) => match pointee(ty) {
+                Some(pointee) => Some(pointee),
+                None => return Err(syn::Error::new_spanned(wrap, "`wrap` expects a `Box<T>`, `Rc<T>` or `Arc<T>` field")),
+            },
+            None => None,
+        };
+
+        let as_ref = attrs.as_ref.as_ref().map(|_| borrowed(ty));
+        let phantom = attrs.field.is_none() && attrs.each.is_none() && attrs.default.is_none() && is_phantom(&field.ty);
+        let custom = match attrs.field {
+            Some((_, ty, build)) => Some((ty, build)),
+            None if phantom => Some((field.ty.clone(), syn::parse_quote!(::core::marker::PhantomData))),
+            None => None,
+        };
 
         Ok(Self {
             option,
             ident: field.ident.as_ref().unwrap(),
+            setter: prefixed(&container.setter_prefix, field.ident.as_ref().unwrap()),
             ty,
-            each: attrs.each.map(|f| format_ident!("{}", f.value())),
+            into: attrs.into.is_some() || (container.into && attrs.each.is_none() && as_ref.is_none()),
+            each: attrs.each.map(|each| prefixed(&container.setter_prefix, &each)),
+            default: attrs.default.map(|(_, expr)| expr),
+            debug: debug_format(&field.attrs),
+            sensitive: attrs.sensitive.is_some(),
+            debug_skip: debug_skip(&field.attrs),
+            docs: field.attrs.iter().filter(|attr| attr.path.is_ident("doc")).collect(),
+            deprecated: field.attrs.iter().filter(|attr| attr.path.is_ident("deprecated")).collect(),
+            cfgs: field.attrs.iter().filter(|attr| attr.path.is_ident("cfg")).collect(),
+            custom,
+            wrap,
+            as_ref,
+            aliases: attrs.aliases.iter().map(|alias| prefixed(&container.setter_prefix, alias)).collect(),
+            custom_setter: attrs.custom_setter,
+            by_name: attrs.by_name.unwrap_or(true),
+            phantom,
+            warnings: attrs.warnings,
         })
     }
 
+    fn cfg(&self) -> TokenStream {
+        let cfgs = &self.cfgs;
+        quote!(#(#cfgs)*)
+    }
+
+    // Whether the builder's Debug shows the field as derived Debug would.
+    fn plain_debug(&self) -> bool {
+        self.debug.is_none() && !self.sensitive && !self.debug_skip
+    }
+
+    // Whether the field gets the generated setters.
+    fn has_setters(&self) -> bool {
+        self.custom_setter.is_none() && !self.phantom
+    }
+
     fn standard(&self) -> bool {
-        !self.option() && !self.each()
+        !self.option() && !self.each() && !self.default() && !self.custom()
+    }
+
+    fn custom(&self) -> bool {
+        self.custom.is_some()
+    }
+
+    // What the builder holds, without the `Option` it may be wrapped in.
+    fn held_ty(&self) -> &Type {
+        self.custom.as_ref().map_or(self.ty, |(ty, _)| ty)
+    }
+
+    fn default(&self) -> bool {
+        self.default.is_some()
+    }
+
+    // Whether the builder holds the field as `Option<ty>`.
+    fn optional_in_builder(&self) -> bool {
+        self.standard() || self.default()
     }
 
     fn option(&self) -> bool {
         self.option
     }
 
+    // The type of the builder's field.
+    fn builder_ty(&self) -> TokenStream {
+        let ty = self.held_ty();
+        if self.optional_in_builder() || self.option() {
+            quote!(::core::option::Option<#ty>)
+        } else {
+            quote!(#ty)
+        }
+    }
+
+    // Whether the builder holds an `each` field's collection itself. Of an
+    // `Option<Vec<T>>` it holds the `Option`, as of any other `Option` field.
     fn each(&self) -> bool {
-        self.each.is_some()
+        self.each.is_some() && !self.option()
+    }
+
+    // The collection `each` setters add to, and the bounds that needs.
+    fn each_target(&self) -> (TokenStream, TokenStream) {
+        let (ident, ty) = (self.ident, self.ty);
+        if self.option() {
+            (quote!(self.#ident.get_or_insert_with(::core::default::Default::default)), quote!(#ty: ::core::default::Default,))
+        } else {
+            (quote!(&mut self.#ident), quote!())
+        }
     }
 
     fn standard_ident(&self) -> Option<&'a Ident> {
-        self.standard().then(|| self.ident)
+        self.standard().then_some(self.ident)
     }
 
     fn standard_ty(&self) -> Option<&'a Type> {
-        self.standard().then(|| self.ty)
+        self.standard().then_some(self.ty)
     }
 
-    fn option_ident(&self) -> Option<&'a Ident> {
-        self.option().then(|| self.ident)
+    // The type a whole-value setter takes, and how to get the field from
+    // its argument `val`.
+    fn param(&self) -> (TokenStream, TokenStream) {
+        let ty = self.wrap.unwrap_or_else(|| self.held_ty());
+        let (param, val) = if let Some(borrowed) = &self.as_ref {
+            (
+                quote!(impl ::core::convert::AsRef<#borrowed>),
+                quote!(<#ty as ::core::convert::From<&#borrowed>>::from(::core::convert::AsRef::<#borrowed>::as_ref(&val))),
+            )
+        } else if self.into {
+            (quote!(impl ::core::convert::Into<#ty>), quote!(::core::convert::Into::into(val)))
+        } else {
+            (quote!(#ty), quote!(val))
+        };
+        match self.wrap {
+            Some(_) => {
+                let held = self.held_ty();
+                (param, quote!(<#held>::new(#val)))
+            }
+            None => (param, val),
+        }
     }
 
-    fn option_ty(&self) -> Option<&'a Type> {
-        self.option().then(|| self.ty)
+    // The names of the setter of a field that is set as a whole: its own
+    // and its aliases. An `each` field has one too unless its one-at-a-time
+    // setter has the same name.
+    fn setter_names(&self) -> impl Iterator<Item = &Ident> {
+        let own = (self.each.as_ref() != Some(&self.setter)).then_some(&self.setter);
+        own.into_iter().chain(&self.aliases)
+    }
+
+    // The names of all the setters of the field, to tell whether one is
+    // taken by a method the builder would have otherwise.
+    fn method_names(&self) -> impl Iterator<Item = &Ident> {
+        let setters = self.has_setters().then(|| self.setter_names().chain(&self.each));
+        setters.into_iter().flatten()
+    }
+
+    fn setter(&self, receiver: &Receiver) -> Option<TokenStream> {
+        if !self.has_setters() {
+            return None;
+        }
+        let Receiver { param: this, ret, constness } = receiver;
+
+        let (ident, docs, deprecated, cfg) = (self.ident, &self.docs, &self.deprecated, self.cfg());
+        let (param, mut val) = self.param();
+        if self.optional_in_builder() || self.option() {
+            val = quote!(::core::option::Option::Some(#val));
+        }
+        let setters = self.setter_names().map(|setter| quote! {
+            #cfg
+            #(#docs)*
+            #(#deprecated)*
+            pub #constness fn #setter(#this, val: #param) -> #ret {
+                self.#ident = #val;
+                self
+            }
+        });
+        Some(quote!(#(#setters)*))
+    }
+
+    // `get_field()`: `Option<&T>` for a field set as a whole, a reference to
+    // what the builder holds otherwise.
+    fn getter(&self) -> TokenStream {
+        if self.phantom {
+            return TokenStream::new();
+        }
+        let (ident, ty, cfg) = (self.ident, self.held_ty(), self.cfg());
+        let getter = format_ident!("get_{}", ident);
+        let doc = format!("Returns `{}` as set so far.", ident);
+        let (ret, body) = if self.optional_in_builder() || self.option() {
+            (quote!(::core::option::Option<&#ty>), quote!(self.#ident.as_ref()))
+        } else {
+            (quote!(&#ty), quote!(&self.#ident))
+        };
+        quote! {
+            #cfg
+            #[doc = #doc]
+            pub fn #getter(&self) -> #ret {
+                #body
+            }
+        }
+    }
+
+    // `unset_field()`, putting the field back to how `Foo::builder()` starts
+    // it out.
+    fn unset(&self, receiver: &Receiver) -> TokenStream {
+        if self.phantom {
+            return TokenStream::new();
+        }
+        let Receiver { param: this, ret, constness } = receiver;
+        let (ident, cfg) = (self.ident, self.cfg());
+        let unset = format_ident!("unset_{}", ident);
+        let doc = format!("Unsets `{}`, as if none of its setters had been called.", ident);
+        let initial = if self.optional_in_builder() || self.option() {
+            quote!(::core::option::Option::None)
+        } else {
+            quote!(::core::default::Default::default())
+        };
+        quote! {
+            #cfg
+            #[doc = #doc]
+            pub #constness fn #unset(#this) -> #ret {
+                self.#ident = #initial;
+                self
+            }
+        }
+    }
+
+    // The setter adding one item to an `each` field. For maps it takes the
+    // key and the value separately, for other std collections the item; of
+    // other types, anything they `Extend` with.
+    fn each_setter(&self, receiver: &Receiver) -> Option<TokenStream> {
+        let each = self.each.as_ref().filter(|_| self.has_setters())?;
+        let Receiver { param: this, ret, .. } = receiver;
+        let (ty, docs, deprecated, cfg) = (self.ty, &self.docs, &self.deprecated, self.cfg());
+        let (target, bounds) = self.each_target();
+        Some(match map_types(ty) {
+            Some((key, value)) => quote! {
+                #cfg
+                #(#docs)*
+                #(#deprecated)*
+                pub fn #each(#this, key: #key, value: #value) -> #ret where #bounds {
+                    ::core::iter::Extend::extend(#target, [(key, value)]);
+                    self
+                }
+            },
+            None => match collection_item(ty) {
+                Some(item) => quote! {
+                    #cfg
+                    #(#docs)*
+                    #(#deprecated)*
+                    pub fn #each(#this, val: #item) -> #ret where #bounds {
+                        ::core::iter::Extend::extend(#target, [val]);
+                        self
+                    }
+                },
+                None => quote! {
+                    #cfg
+                    #(#docs)*
+                    #(#deprecated)*
+                    pub fn #each<__T>(#this, val: __T) -> #ret where #bounds #ty: ::core::iter::Extend<__T> {
+                        ::core::iter::Extend::extend(#target, [val]);
+                        self
+                    }
+                },
+            },
+        })
+    }
+
+    // `extend_field()`, adding every item of an iterator to an `each` field.
+    fn extend_setter(&self, receiver: &Receiver) -> Option<TokenStream> {
+        if self.each.is_none() || !self.has_setters() {
+            return None;
+        }
+        let Receiver { param: this, ret, .. } = receiver;
+        let (ident, ty, deprecated, cfg) = (self.ident, self.ty, &self.deprecated, self.cfg());
+        let (target, bounds) = self.each_target();
+        let extend = format_ident!("extend_{}", ident);
+        let doc = format!("Adds every item of `vals` to `{}`.", ident);
+        // An optional field stays `None` if `vals` turns out empty.
+        let body = if self.option() {
+            quote! {
+                let mut vals = ::core::iter::IntoIterator::into_iter(vals);
+                if let ::core::option::Option::Some(first) = ::core::iter::Iterator::next(&mut vals) {
+                    ::core::iter::Extend::extend(#target, ::core::iter::Iterator::chain(::core::iter::once(first), vals));
+                }
+            }
+        } else {
+            quote!(::core::iter::Extend::extend(#target, vals);)
+        };
+        Some(quote! {
+            #cfg
+            #[doc = #doc]
+            #(#deprecated)*
+            pub fn #extend<__I>(#this, vals: __I) -> #ret
+            where
+                __I: ::core::iter::IntoIterator,
+                #bounds
+                #ty: ::core::iter::Extend<__I::Item>,
+            {
+                #body
+                self
+            }
+        })
+    }
+
+    fn custom_ident(&self) -> Option<&'a Ident> {
+        self.custom().then_some(self.ident)
     }
 
-    fn each_ident<'s>(&'s self) -> Option<&'s Ident> {
-        self.each().then(|| self.each.as_ref().unwrap())
+    fn custom_ty(&self) -> Option<&Type> {
+        self.custom.as_ref().map(|(ty, _)| ty)
+    }
+
+    fn custom_expr(&self) -> Option<&Expr> {
+        self.custom.as_ref().map(|(_, expr)| expr)
+    }
+
+    // What `Foo::builder()` starts a `field(...)` field out as. `PhantomData`
+    // is spelled out, as `Default::default()` isn't a `const fn`.
+    fn custom_init(&self) -> Option<TokenStream> {
+        match self.phantom {
+            true => Some(quote!(::core::marker::PhantomData)),
+            false => self.custom().then(|| quote!(::core::default::Default::default())),
+        }
+    }
+
+    fn default_ident(&self) -> Option<&'a Ident> {
+        self.default().then_some(self.ident)
+    }
+
+    fn default_ty(&self) -> Option<&'a Type> {
+        self.default().then_some(self.ty)
+    }
+
+    fn default_expr(&self) -> Option<&TokenStream> {
+        self.default.as_ref()
+    }
+
+    fn option_ident(&self) -> Option<&'a Ident> {
+        self.option().then_some(self.ident)
+    }
+
+    fn option_ty(&self) -> Option<&'a Type> {
+        self.option().then_some(self.ty)
     }
 
     fn each_ty(&self) -> Option<&'a Type> {
-        self.each().then(|| self.ty)
+        self.each().then_some(self.ty)
     }
 
     fn each_owner(&self) -> Option<&'a Ident> {
-        self.each().then(|| self.ident)
+        self.each().then_some(self.ident)
     }
 }
 
-fn builder(input: &DeriveInput) -> syn::Result<TokenStream> {
+// `#[builder(derive(...))]` and `#[builder(serde)]` on a builder type `ty`
+// that `Foo::builder()` returns: the derive attributes and the `Default` impl.
+fn derives(input: &DeriveInput, container: &ContainerAttrs, ty: &TokenStream) -> (TokenStream, Option<TokenStream>) {
+    let (default, derived): (Vec<_>, Vec<_>) = container.derive.iter().partition(|path| path.is_ident("Default"));
+    let derive = (!derived.is_empty()).then(|| quote!(#[derive(#(#derived),*)]));
+    // Fields absent from the input start out as in `Foo::builder()`.
+    let serde = container.serde.then(|| {
+        let (_, ty_generics, _) = input.generics.split_for_impl();
+        let builder = format!("{}{}::builder", input.ident, ty_generics.as_turbofish().to_token_stream());
+        match &container.serde_crate {
+            Some((krate, path)) => quote! {
+                #[derive(#path::Serialize, #path::Deserialize)]
+                #[serde(crate = #krate, default = #builder)]
+            },
+            None => quote! {
+                #[derive(::serde::Serialize, ::serde::Deserialize)]
+                #[serde(default = #builder)]
+            },
+        }
+    });
+    let derive = quote!(#derive #serde);
+    let default = default.first().map(|path| {
+        let ident = &input.ident;
+        let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+        let turbofish = ty_generics.as_turbofish();
+        quote_spanned! {path.span()=>
+            impl #impl_generics ::core::default::Default for #ty #where_clause {
+                fn default() -> Self {
+                    #ident #turbofish::builder()
+                }
+            }
+        }
+    });
+    (derive, default)
+}
+
+// `From<Foo>` for the builder type `ty` with every field set, and
+// `Foo::to_builder()` to edit a copy. A `field(...)` field can't be turned
+// back into what the builder holds, so neither is generated then. The
+// typestate builder holds its required fields as they are.
+fn to_builder(input: &DeriveInput, builder_ident: &Ident, ty: &TokenStream, fields: &[TargetField], typestate: bool) -> Option<TokenStream> {
+    if fields.iter().any(|f| f.custom() && !f.phantom) {
+        return None;
+    }
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let values = fields.iter().map(|f| {
+        let (cfg, ident) = (f.cfg(), f.ident);
+        if f.optional_in_builder() && !(typestate && f.standard()) {
+            quote!(#cfg #ident: ::core::option::Option::Some(value.#ident),)
+        } else {
+            quote!(#cfg #ident: value.#ident,)
+        }
+    });
+    let marker = typestate.then(|| quote!(__marker: ::core::marker::PhantomData,));
+    let doc = format!("Returns a builder with every field set from a clone of this `{}`.", ident);
+    Some(quote! {
+        impl #impl_generics ::core::convert::From<#ident #ty_generics> for #ty #where_clause {
+            #[allow(deprecated)]
+            fn from(value: #ident #ty_generics) -> Self {
+                #builder_ident {
+                    #(#values)*
+                    #marker
+                }
+            }
+        }
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #[doc = #doc]
+            pub fn to_builder(&self) -> #ty
+            // A plain `Self: Clone` is a trivially false bound on a struct
+            // that isn't `Clone`; the `for<'__c>` defers it to the caller.
+            where
+                for<'__c> Self: ::core::clone::Clone,
+            {
+                ::core::convert::From::from(::core::clone::Clone::clone(self))
+            }
+        }
+    })
+}
+
+// Docs of `Foo::builder`, `FooBuilder` and `FooBuilder::build`.
+fn docs(ident: &Ident) -> [String; 3] {
+    [
+        format!("Returns a builder for `{}`.", ident),
+        format!("A builder for `{}`.", ident),
+        format!("Builds the `{}`.", ident),
+    ]
+}
+
+fn builder(input: &DeriveInput, container: &ContainerAttrs) -> syn::Result<TokenStream> {
     let ident = &input.ident;
     let builder_ident = format_ident!("{}Builder", ident);
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     let fields = if let DeriveInput { data: Data::Struct(data), .. } = input {
-        data.fields.iter().map(TargetField::from).collect::<syn::Result<Vec<_>>>()?
+        data.fields.iter().map(|f| TargetField::from(f, container)).collect::<syn::Result<Vec<_>>>()?
     } else {
         return Err(syn::Error::new(input.span(), "enum or union not supported."));
     };
 
+    if container.typestate {
+        if let Some(cfg) = fields.iter().find_map(|f| f.cfgs.first()) {
+            return Err(syn::Error::new_spanned(cfg, "`#[cfg]` on a field isn't supported with `typestate`"));
+        }
+        if let Some(custom) = fields.iter().find_map(|f| f.custom_setter.as_ref()) {
+            return Err(syn::Error::new_spanned(custom, "`setter(custom)` isn't supported with `typestate`"));
+        }
+        return Ok(typestate(input, container, &builder_ident, &fields));
+    }
+
+    if container.const_fn {
+        if let Some(f) = fields.iter().find(|f| f.each.is_some() || (f.custom() && !f.phantom) || f.into || f.wrap.is_some() || f.as_ref.is_some()) {
+            return Err(syn::Error::new_spanned(f.ident, "`const` doesn't support `each`, `field(...)`, `into`, `wrap` or `as_ref`, which call trait methods"));
+        }
+    }
+
+    // The `#[cfg]`s of the fields of each kind, in the same order.
+    let cfgs = |kind: fn(&TargetField) -> bool| fields.iter().filter(|f| kind(f)).map(TargetField::cfg).collect::<Vec<_>>();
+    let (fcfgs, def_cfgs, opt_cfgs) = (cfgs(|f| f.standard()), cfgs(|f| f.default()), cfgs(|f| f.option()));
+    let (each_cfgs, cus_cfgs) = (cfgs(|f| f.each()), cfgs(|f| f.custom()));
+
     let fidents = fields.iter().filter_map(TargetField::standard_ident).collect::<Vec<_>>();
     let ftys = fields.iter().filter_map(TargetField::standard_ty).collect::<Vec<_>>();
 
+    let def_fidents = fields.iter().filter_map(TargetField::default_ident).collect::<Vec<_>>();
+    let def_ftys = fields.iter().filter_map(TargetField::default_ty).collect::<Vec<_>>();
+    let def_exprs = fields.iter().filter_map(TargetField::default_expr).collect::<Vec<_>>();
+
     let opt_fidents = fields.iter().filter_map(TargetField::option_ident).collect::<Vec<_>>();
     let opt_ftys = fields.iter().filter_map(TargetField::option_ty).collect::<Vec<_>>();
 
-    let eachs = fields.iter().filter_map(TargetField::each_ident).collect::<Vec<_>>();
     let each_tys = fields.iter().filter_map(TargetField::each_ty).collect::<Vec<_>>();
     let each_owners = fields.iter().filter_map(TargetField::each_owner).collect::<Vec<_>>();
 
+    let cus_fidents = fields.iter().filter_map(TargetField::custom_ident).collect::<Vec<_>>();
+    let cus_ftys = fields.iter().filter_map(TargetField::custom_ty).collect::<Vec<_>>();
+    let cus_exprs = fields.iter().filter_map(TargetField::custom_expr).collect::<Vec<_>>();
+    let cus_inits = fields.iter().filter_map(TargetField::custom_init).collect::<Vec<_>>();
+
+    let constness = container.const_fn.then(|| quote!(const));
+    let receiver = Receiver { constness: constness.clone(), ..Receiver::new(container.owned) };
+    let setters = fields.iter().filter_map(|f| f.setter(&receiver));
+    let each_setters = fields.iter().filter_map(|f| f.each_setter(&receiver));
+    let extend_setters = fields.iter().filter_map(|f| f.extend_setter(&receiver));
+    let getters = fields.iter().map(TargetField::getter);
+    let unsetters = fields.iter().map(|f| f.unset(&receiver));
+    // A setter named `clear` keeps its name; the builder has no `clear()`
+    // then.
+    let clear = (!fields.iter().flat_map(TargetField::method_names).any(|name| name == "clear")).then(|| {
+        let Receiver { param: this, ret, .. } = &receiver;
+        let turbofish = ty_generics.as_turbofish();
+        let body = if container.owned {
+            quote!(#ident #turbofish::builder())
+        } else {
+            quote! {
+                *self = #ident #turbofish::builder();
+                self
+            }
+        };
+        quote! {
+            /// Unsets every field, as if the builder had just been created.
+            pub #constness fn clear(#this) -> #ret {
+                #body
+            }
+        }
+    });
+
+    // An owned builder moves the values out, a borrowed one takes them and
+    // is left empty, so no field needs to be `Clone`.
+    let take = |idents: &[&Ident], each: bool| -> Vec<TokenStream> {
+        idents.iter().map(|ident| if container.owned {
+            quote!(self.#ident)
+        } else if each {
+            quote!(::core::mem::take(&mut self.#ident))
+        } else {
+            quote!(self.#ident.take())
+        }).collect()
+    };
+    let values = take(&fidents, false);
+    let (def_values, opt_values, each_values) = (take(&def_fidents, false), take(&opt_fidents, false), take(&each_owners, true));
+    let build_this = if container.owned { quote!(self) } else { quote!(&mut self) };
+    let def_flags = def_fidents.iter().map(|f| format_ident!("__{}_set", f)).collect::<Vec<_>>();
+
+    // With `validate`, `build()` returns a `Result` whose error tells a
+    // missing field from a rejected value.
+    let error_ident = format_ident!("{}Error", builder_ident);
+    let (build_ret, missing, finish, error_def) = match &container.validate {
+        None => {
+            let missing = fidents.iter().map(|_| quote!(return ::core::option::Option::None)).collect::<Vec<_>>();
+            let finish = quote!(::core::option::Option::Some(value));
+            (quote!(::core::option::Option<#ident #ty_generics>), missing, finish, None)
+        }
+        Some((validate, error)) => {
+            let missing = fidents.iter().map(|f| {
+                let name = f.to_string();
+                quote!(return ::core::result::Result::Err(#error_ident::Missing(#name)))
+            }).collect();
+            // A rejected value goes back into a borrowed builder, so that
+            // the caller can fix a field and build again. Defaults go back
+            // only where a value had been set.
+            let restore = (!container.owned).then(|| quote! {
+                let #ident {
+                    #(#fcfgs #fidents,)*
+                    #(#def_cfgs #def_fidents,)*
+                    #(#opt_cfgs #opt_fidents,)*
+                    #(#each_cfgs #each_owners,)*
+                    ..
+                } = value;
+                #(#fcfgs { self.#fidents = ::core::option::Option::Some(#fidents); })*
+                #(#def_cfgs if #def_flags { self.#def_fidents = ::core::option::Option::Some(#def_fidents); })*
+                #(#opt_cfgs { self.#opt_fidents = #opt_fidents; })*
+                #(#each_cfgs { self.#each_owners = #each_owners; })*
+            });
+            let finish = quote! {
+                match #validate(&value) {
+                    ::core::result::Result::Ok(()) => ::core::result::Result::Ok(value),
+                    ::core::result::Result::Err(err) => {
+                        #restore
+                        ::core::result::Result::Err(#error_ident::Invalid(err))
+                    }
+                }
+            };
+            let msg = format!("Why `{}::build` failed.", builder_ident);
+            let error_def = quote! {
+                #[doc = #msg]
+                #[derive(::core::fmt::Debug)]
+                pub enum #error_ident {
+                    /// The named required field was not set.
+                    Missing(&'static str),
+                    /// The validation function rejected the value.
+                    Invalid(#error),
+                }
+            };
+            (quote!(::core::result::Result<#ident #ty_generics, #error_ident>), missing, finish, Some(error_def))
+        }
+    };
+
+    // `TryFrom` the builder, and from `&mut` it unless `build()` consumes it.
+    // Without `validate` the error is `()`, as all `build()` tells is `None`.
+    let (error_ty, to_result) = match &container.validate {
+        None => (quote!(()), quote!(.ok_or(()))),
+        Some(..) => (quote!(#error_ident), quote!()),
+    };
+    let builder_ty = quote!(#builder_ident #ty_generics);
+    let by_value = if container.owned { quote!(builder) } else { quote!(mut builder) };
+    let mut conversions = vec![(impl_generics.to_token_stream(), builder_ty.clone(), by_value)];
+    if !container.owned {
+        let mut generics = generics.clone();
+        generics.params.insert(0, syn::parse_quote!('__b));
+        let impl_generics = generics.split_for_impl().0.to_token_stream();
+        conversions.push((impl_generics, quote!(&'__b mut #builder_ty), quote!(builder)));
+    }
+    let conversions = conversions.into_iter().map(|(impl_generics, from, param)| quote! {
+        impl #impl_generics ::core::convert::TryFrom<#from> for #ident #ty_generics #where_clause {
+            type Error = #error_ty;
+
+            fn try_from(#param: #from) -> ::core::result::Result<Self, #error_ty> {
+                builder.build() #to_result
+            }
+        }
+    });
+
+    // A borrowed builder fails for a missing field before taking anything,
+    // so a failed `build()` leaves it as it was. With `validate`, it also
+    // notes which defaults were set, see `restore`.
+    let precheck = (!container.owned).then(|| {
+        let flags = container.validate.is_some().then(|| quote! {
+            #(#def_cfgs let #def_flags = self.#def_fidents.is_some();)*
+        });
+        quote! {
+            #(#fcfgs if self.#fidents.is_none() { #missing })*
+            #flags
+        }
+    });
+
+    let warnings = fields.iter().map(|f| &f.warnings);
+    let [builder_doc, struct_doc, mut build_doc] = docs(ident);
+    if !container.owned {
+        build_doc.push_str(" The values are moved out, leaving the builder empty, unless `build()` fails, which leaves it as it was.");
+    }
+    let (derive_debug, debug_impl) = debug_impl(input, &builder_ident, &fields);
+    let (build_partial, partial_def) = partial(input, &builder_ident, &fields);
+    let (set, field_enum) = match container.field_enum {
+        true => field_enum(input, &builder_ident, &receiver, &fields)?,
+        false => Default::default(),
+    };
+    let (set_by_name, set_error) = by_name(&builder_ident, &receiver, &fields);
+    let call = container.call.as_ref().map(|call| {
+        let output = call.output();
+        let (ret, ok) = match &container.validate {
+            None => (quote!(::core::option::Option<#output>), quote!(::core::option::Option::Some)),
+            Some(..) => (quote!(::core::result::Result<#output, #error_ident>), quote!(::core::result::Result::Ok)),
+        };
+        call.method(ident, &build_this, &ret, Some(ok))
+    });
+    let (derive, default_impl) = derives(input, container, &quote!(#builder_ident #ty_generics));
+    let to_builder = to_builder(input, &builder_ident, &builder_ty, &fields, false);
+
     Ok(quote! {
-        impl #ident {
-            pub fn builder() -> #builder_ident {
+        #(#warnings)*
+
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #[doc = #builder_doc]
+            pub #constness fn builder() -> #builder_ident #ty_generics {
                 #builder_ident {
-                    #(#fidents: None,)*
-                    #(#opt_fidents: None,)*
-                    #(#each_owners: ::std::default::Default::default(),)*
+                    #(#fcfgs #fidents: ::core::option::Option::None,)*
+                    #(#def_cfgs #def_fidents: ::core::option::Option::None,)*
+                    #(#opt_cfgs #opt_fidents: ::core::option::Option::None,)*
+                    #(#cus_cfgs #cus_fidents: #cus_inits,)*
+                    #(#each_cfgs #each_owners: ::core::default::Default::default(),)*
                 }
             }
         }
 
-        #[derive(Debug)]
-        pub struct #builder_ident {
-            #(#fidents: ::std::option::Option<#ftys>,)*
-            #(#opt_fidents: ::std::option::Option<#opt_ftys>,)*
-            #(#each_owners: #each_tys,)*
+        #derive_debug
+        #derive
+        #[doc = #struct_doc]
+        pub struct #builder_ident #generics #where_clause {
+            #(#fcfgs #fidents: ::core::option::Option<#ftys>,)*
+            #(#def_cfgs #def_fidents: ::core::option::Option<#def_ftys>,)*
+            #(#opt_cfgs #opt_fidents: ::core::option::Option<#opt_ftys>,)*
+            #(#cus_cfgs #cus_fidents: #cus_ftys,)*
+            #(#each_cfgs #each_owners: #each_tys,)*
+        }
+
+        impl #impl_generics #builder_ident #ty_generics #where_clause {
+            #(#setters)*
+                #(#each_setters)*
+                #(#extend_setters)*
+                #(#getters)*
+                #(#unsetters)*
+                #clear
+
+                #[doc = #build_doc]
+                #[allow(deprecated)]
+                pub #constness fn build(#build_this) -> #build_ret {
+                    #precheck
+                    #(#cus_cfgs let #cus_fidents = #cus_exprs;)*
+                    #(
+                        #fcfgs
+                        let #fidents = match #values {
+                            ::core::option::Option::Some(val) => val,
+                            ::core::option::Option::None => #missing,
+                        };
+                    )*
+                    #(
+                        #def_cfgs
+                        let #def_fidents = match #def_values {
+                            ::core::option::Option::Some(val) => val,
+                            ::core::option::Option::None => #def_exprs,
+                        };
+                    )*
+                    #(#opt_cfgs let #opt_fidents = #opt_values;)*
+                    #(#each_cfgs let #each_owners = #each_values;)*
+
+                    let value = #ident {
+                        #(#fcfgs #fidents,)*
+                        #(#def_cfgs #def_fidents,)*
+                        #(#opt_cfgs #opt_fidents,)*
+                        #(#cus_cfgs #cus_fidents,)*
+                        #(#each_cfgs #each_owners,)*
+                    };
+                    #finish
+                }
+
+                #build_partial
+
+                #set
+
+                #set_by_name
+
+                #call
         }
 
-        impl #builder_ident {
+        #default_impl
+
+        #(#conversions)*
+
+        #to_builder
+
+        #error_def
+
+        #partial_def
+
+        #field_enum
+
+        #set_error
+
+        #debug_impl
+    })
+}
+
+// `build_partial()` and the `FooPartial` it returns: the builder's fields as
+// they are so far, borrowed, to report what is still missing before `build()`.
+fn partial(input: &DeriveInput, builder_ident: &Ident, fields: &[TargetField]) -> (TokenStream, TokenStream) {
+    let where_clause = &input.generics.where_clause;
+    let partial_ident = format_ident!("{}Partial", input.ident);
+    let mut generics = input.generics.clone();
+    generics.params.insert(0, syn::parse_quote!('__p));
+    let (partial_impl_generics, partial_ty_generics, _) = generics.split_for_impl();
+    let params = input.generics.params.iter().map(|param| match param {
+        syn::GenericParam::Type(ty) => ty.ident.to_token_stream(),
+        syn::GenericParam::Lifetime(lt) => lt.lifetime.to_token_stream(),
+        syn::GenericParam::Const(c) => c.ident.to_token_stream(),
+    });
+
+    let idents = fields.iter().map(|f| f.ident).collect::<Vec<_>>();
+    let cfgs = fields.iter().map(TargetField::cfg).collect::<Vec<_>>();
+    let tys = fields.iter().map(TargetField::builder_ty);
+    let field_docs = idents.iter().map(|ident| format!("`{}` as set so far.", ident));
+    let required = fields.iter().filter_map(TargetField::standard_ident).collect::<Vec<_>>();
+    let req_cfgs = fields.iter().filter(|f| f.standard()).map(TargetField::cfg);
+    let names = required.iter().map(|ident| ident.to_string());
+    let count = required.len();
+
+    let method_doc = format!("Borrows the fields set so far, e.g. to report the missing ones before `{}::build`.", builder_ident);
+    let struct_doc = format!("The fields of `{}` as set so far, see `{0}::build_partial`.", builder_ident);
+    let method = quote! {
+        #[doc = #method_doc]
+        pub fn build_partial(&self) -> #partial_ident<'_, #(#params),*> {
+            #partial_ident {
+                #(#cfgs #idents: &self.#idents,)*
+            }
+        }
+    };
+    let def = quote! {
+        #[doc = #struct_doc]
+        pub struct #partial_ident #generics #where_clause {
             #(
-                pub fn #fidents(&mut self, val: #ftys) -> &mut Self {
-                    self.#fidents = Some(val);
-                    self
+                #cfgs
+                #[doc = #field_docs]
+                pub #idents: &'__p #tys,
+            )*
+        }
+
+        impl #partial_impl_generics #partial_ident #partial_ty_generics #where_clause {
+            /// The names of the required fields not set yet.
+            pub fn missing(&self) -> impl ::core::iter::Iterator<Item = &'static str> {
+                // A field configured out is never missing.
+                let fields: [(&'static str, bool); #count] = [#((#names, {
+                    #[allow(unused_variables)]
+                    let missing = false;
+                    #req_cfgs
+                    let missing = self.#required.is_none();
+                    missing
+                })),*];
+                ::core::iter::Iterator::filter_map(::core::iter::IntoIterator::into_iter(fields), |(name, missing)| {
+                    if missing { ::core::option::Option::Some(name) } else { ::core::option::Option::None }
+                })
+            }
+        }
+    };
+    (method, def)
+}
+
+// `FooBuilder::set_by_name()`, setting the field of a name from a string
+// parsed with `FromStr`; for an `each` field, other than a map, it adds an
+// item. It can be called when every such type is `FromStr`, hence the
+// `for<'__s>` on the bounds, which otherwise would be rejected up front.
+// Fields with `setter(custom)`, `by_name = false` or of `PhantomData` are left
+// out, as are references, which nothing parses to and whose bounds would be
+// ambiguous for two lifetimes.
+fn by_name(builder_ident: &Ident, receiver: &Receiver, fields: &[TargetField]) -> (TokenStream, TokenStream) {
+    let error_ident = format_ident!("{}SetError", builder_ident);
+    let mut bounds = Vec::new();
+    let arms = fields.iter().filter(|f| f.has_setters() && f.by_name).filter_map(|f| {
+        let (ident, cfg) = (f.ident, f.cfg());
+        let name = ident.unraw().to_string();
+        let (parsed, assign) = if f.each.is_some() {
+            let item = type_arg(f.ty).filter(|_| map_types(f.ty).is_none())?;
+            let (ty, (target, each_bounds)) = (f.ty, f.each_target());
+            bounds.push(quote!(#each_bounds #ty: ::core::iter::Extend<#item>,));
+            (item, quote!(::core::iter::Extend::extend(#target, [val])))
+        } else {
+            let parsed = f.wrap.unwrap_or_else(|| f.held_ty());
+            let mut val = match f.wrap {
+                Some(_) => {
+                    let held = f.held_ty();
+                    quote!(<#held>::new(val))
                 }
+                None => quote!(val),
+            };
+            if f.optional_in_builder() || f.option() {
+                val = quote!(::core::option::Option::Some(#val));
+            }
+            (parsed, quote!(self.#ident = #val))
+        };
+        if let Type::Reference(..) = parsed {
+            return None;
+        }
+        bounds.push(quote!(for<'__s> #parsed: ::core::str::FromStr,));
+        Some(quote! {
+            #cfg
+            #name => {
+                let val = <#parsed as ::core::str::FromStr>::from_str(value).map_err(|_| #error_ident::Invalid(#name))?;
+                #assign;
+                ::core::result::Result::Ok(self)
+            }
+        })
+    }).collect::<Vec<_>>();
+
+    let Receiver { param: this, ret, .. } = receiver;
+    let error_doc = format!("Why `{}::set_by_name` failed.", builder_ident);
+    let method = quote! {
+        /// Sets the field named `name` to `value` parsed with `FromStr`, or
+        /// adds it to an `each` field.
+        pub fn set_by_name(#this, name: &str, value: &str) -> ::core::result::Result<#ret, #error_ident>
+        where
+            #(#bounds)*
+        {
+            match name {
+                #(#arms)*
+                _ => ::core::result::Result::Err(#error_ident::Unknown),
+            }
+        }
+    };
+    let def = quote! {
+        #[doc = #error_doc]
+        #[derive(::core::fmt::Debug, ::core::cmp::PartialEq)]
+        pub enum #error_ident {
+            /// No field can be set by that name.
+            Unknown,
+            /// The value didn't parse as the named field's type.
+            Invalid(&'static str),
+        }
+    };
+    (method, def)
+}
+
+// `#[builder(field_enum)]`: `FooField`, with a variant per field holding a
+// value for it, and `FooBuilder::set()` taking one, to drive the builder
+// from data. Fields with `setter(custom)`, whose setters are the user's, and
+// `PhantomData` ones are left out. A hidden variant keeps the struct's
+// parameters in use. Like the setters, the variants may hold types more
+// private than the enum.
+fn field_enum(input: &DeriveInput, builder_ident: &Ident, receiver: &Receiver, fields: &[TargetField]) -> syn::Result<(TokenStream, TokenStream)> {
+    if let Some(name) = fields.iter().flat_map(TargetField::method_names).find(|name| *name == "set") {
+        return Err(syn::Error::new_spanned(name, "the setter `set` clashes with `set()` of `builder(field_enum)`; rename it with `builder(setter_prefix = \"...\")`"));
+    }
+
+    let (ident, generics, where_clause) = (&input.ident, &input.generics, &input.generics.where_clause);
+    let (_, ty_generics, _) = generics.split_for_impl();
+    let enum_ident = format_ident!("{}Field", ident);
+    let fields = fields.iter().filter(|f| f.has_setters()).collect::<Vec<_>>();
+
+    let variants = fields.iter().map(|f| format_ident!("{}", camel_case(f.ident), span = f.ident.span())).collect::<Vec<_>>();
+    let cfgs = fields.iter().map(|f| f.cfg()).collect::<Vec<_>>();
+    let tys = fields.iter().map(|f| f.held_ty());
+    let docs = fields.iter().map(|f| format!("A value for `{}`.", f.ident));
+    let sets = fields.iter().map(|f| {
+        let ident = f.ident;
+        if f.optional_in_builder() || f.option() {
+            quote!(self.#ident = ::core::option::Option::Some(val))
+        } else {
+            quote!(self.#ident = val)
+        }
+    });
+
+    let Receiver { param: this, ret, .. } = receiver;
+    let enum_doc = format!("A value for one of the fields of `{}`, see `{}::set`.", ident, builder_ident);
+    let method = quote! {
+        /// Sets the field that `field` holds a value for.
+        pub fn set(#this, field: #enum_ident #ty_generics) -> #ret {
+            match field {
+                #(#cfgs #enum_ident::#variants(val) => #sets,)*
+                #enum_ident::__Marker(_, never) => match never {},
+            }
+            self
+        }
+    };
+    let def = quote! {
+        #[doc = #enum_doc]
+        #[allow(private_interfaces)]
+        pub enum #enum_ident #generics #where_clause {
+            #(
+                #cfgs
+                #[doc = #docs]
+                #variants(#tys),
             )*
-                #(
-                    pub fn #opt_fidents(&mut self, val: #opt_ftys) -> &mut Self {
-                        self.#opt_fidents = Some(val);
-                        self
-                    }
-                )*
-                #(
-                    pub fn #eachs<T>(&mut self, val: T) -> &mut Self where #each_tys: ::std::iter::Extend<T> {
-                        self.#each_owners.extend([val]);
-                        self
+            #[doc(hidden)]
+            __Marker(::core::marker::PhantomData<fn() -> #ident #ty_generics>, ::core::convert::Infallible),
+        }
+    };
+    Ok((method, def))
+}
+
+// `#[builder(typestate)]`: each required field has a type parameter on the
+// builder, `()` until its setter is called and the field's type after, which
+// is also where the value is kept. Setters take and return the builder by
+// value, and `build()` exists only once every required field is set, so a
+// missing one is a compile error instead of `None`. A marker field keeps the
+// struct's own parameters in use when only required fields mention them.
+//
+// Nothing can be missing, so with `validate` the error of `build()` is that of
+// the validation function.
+//
+// The builder derives Debug, unless fields have CustomDebug formats, are
+// skipped by it or are `sensitive`: then it has no Debug at all rather than
+// one that could show what they hide.
+fn typestate(input: &DeriveInput, container: &ContainerAttrs, builder_ident: &Ident, fields: &[TargetField]) -> TokenStream {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let required = fields.iter().filter(|f| f.standard()).collect::<Vec<_>>();
+    let marker = format_ident!("__marker");
+    let states = (0..required.len()).map(|i| format_ident!("__S{}", i)).collect::<Vec<_>>();
+
+    // Defaults of the struct's parameters are dropped, as they would no
+    // longer be trailing.
+    let mut generics = input.generics.clone();
+    for param in &mut generics.params {
+        match param {
+            syn::GenericParam::Type(ty) => (ty.eq_token, ty.default) = (None, None),
+            syn::GenericParam::Const(c) => (c.eq_token, c.default) = (None, None),
+            syn::GenericParam::Lifetime(_) => {}
+        }
+    }
+    generics.params.extend(states.iter().map(|s| -> syn::GenericParam { syn::parse_quote!(#s) }));
+    let (state_impl_generics, _, _) = generics.split_for_impl();
+
+    // The builder's type with the states given by `states`.
+    let params = input.generics.params.iter().map(|param| match param {
+        syn::GenericParam::Type(ty) => ty.ident.to_token_stream(),
+        syn::GenericParam::Lifetime(lt) => lt.lifetime.to_token_stream(),
+        syn::GenericParam::Const(c) => c.ident.to_token_stream(),
+    }).collect::<Vec<_>>();
+    let builder_ty = |states: &[TokenStream]| quote!(#builder_ident<#(#params,)* #(#states,)*>);
+
+    let all = fields.iter().map(|f| f.ident).chain([&marker]).collect::<Vec<_>>();
+    let storage = fields.iter().map(|f| {
+        let ty = f.ty;
+        match required.iter().position(|r| r.ident == f.ident) {
+            Some(i) => states[i].to_token_stream(),
+            None if f.each() => quote!(#ty),
+            None if f.custom() => f.held_ty().to_token_stream(),
+            None => quote!(::core::option::Option<#ty>),
+        }
+    }).chain([quote!(::core::marker::PhantomData<fn() -> #ident #ty_generics>)]);
+    let init = fields.iter().map(|f| match f {
+        f if f.standard() => quote!(()),
+        f if f.each() || f.custom() => quote!(::core::default::Default::default()),
+        _ => quote!(::core::option::Option::None),
+    }).chain([quote!(::core::marker::PhantomData)]);
+
+    let unset = required.iter().map(|_| quote!(())).collect::<Vec<_>>();
+    let generic = states.iter().map(|s| s.to_token_stream()).collect::<Vec<_>>();
+    let set = required.iter().map(|f| f.ty.to_token_stream()).collect::<Vec<_>>();
+    let (unset_ty, generic_ty, set_ty) = (builder_ty(&unset), builder_ty(&generic), builder_ty(&set));
+
+    let receiver = Receiver::new(true);
+    let setters = fields.iter().map(|f| {
+        let (ident, ty) = (f.ident, f.ty);
+        if let Some(i) = required.iter().position(|r| r.ident == f.ident) {
+            let (param, val) = f.param();
+            let (docs, deprecated) = (&f.docs, &f.deprecated);
+            let mut after = generic.clone();
+            after[i] = quote!(#ty);
+            let after = builder_ty(&after);
+            let others = all.iter().filter(|other| **other != ident).collect::<Vec<_>>();
+            let setters = f.setter_names().map(|setter| quote! {
+                #(#docs)*
+                #(#deprecated)*
+                pub fn #setter(self, val: #param) -> #after {
+                    #builder_ident {
+                        #ident: #val,
+                        #(#others: self.#others,)*
                     }
-                )*
+                }
+            });
+            quote!(#(#setters)*)
+        } else {
+            let (each_setter, extend_setter, setter) = (f.each_setter(&receiver), f.extend_setter(&receiver), f.setter(&receiver));
+            quote!(#each_setter #extend_setter #setter)
+        }
+    });
 
-                pub fn build(&mut self) -> ::std::option::Option<#ident> {
-                    #(
-                        let #fidents = if let Some(val) = self.#fidents.clone() {
-                            val
-                        } else {
-                            return None;
-                        };
-                    )*
-                        #(let #opt_fidents = self.#opt_fidents.clone();)*
-                        #(let #each_owners = self.#each_owners.clone();)*
+    let values = fields.iter().map(|f| {
+        let ident = f.ident;
+        match (f.default_expr(), f.custom_expr()) {
+            (Some(expr), _) => quote! {
+                match self.#ident {
+                    ::core::option::Option::Some(val) => val,
+                    ::core::option::Option::None => #expr,
+                }
+            },
+            (_, Some(expr)) => expr.to_token_stream(),
+            _ => quote!(self.#ident),
+        }
+    });
+    let fidents = fields.iter().map(|f| f.ident);
+
+    let (build_ret, finish) = match &container.validate {
+        None => (quote!(#ident #ty_generics), quote!(value)),
+        Some((validate, error)) => (
+            quote!(::core::result::Result<#ident #ty_generics, #error>),
+            quote! {
+                #validate(&value)?;
+                ::core::result::Result::Ok(value)
+            },
+        ),
+    };
+
+    let call = container.call.as_ref().map(|call| {
+        let output = call.output();
+        match &container.validate {
+            None => call.method(ident, &quote!(self), &output, None),
+            Some((_, error)) => {
+                let ret = quote!(::core::result::Result<#output, #error>);
+                call.method(ident, &quote!(self), &ret, Some(quote!(::core::result::Result::Ok)))
+            }
+        }
+    });
+
+    // `build()` can only fail validation, so without it `Foo` is `From` the
+    // builder that has everything set.
+    let conversion = match &container.validate {
+        None => quote! {
+            impl #impl_generics ::core::convert::From<#set_ty> for #ident #ty_generics #where_clause {
+                fn from(builder: #set_ty) -> Self {
+                    builder.build()
+                }
+            }
+        },
+        Some((_, error)) => quote! {
+            impl #impl_generics ::core::convert::TryFrom<#set_ty> for #ident #ty_generics #where_clause {
+                type Error = #error;
+
+                fn try_from(builder: #set_ty) -> ::core::result::Result<Self, #error> {
+                    builder.build()
+                }
+            }
+        },
+    };
+
+    let derive_debug = fields.iter().all(|f| f.plain_debug()).then(|| quote!(#[derive(::core::fmt::Debug)]));
+    let (derive, default_impl) = derives(input, container, &unset_ty);
+    let to_builder = to_builder(input, builder_ident, &set_ty, fields, true);
+    let warnings = fields.iter().map(|f| &f.warnings);
+    let [builder_doc, struct_doc, build_doc] = docs(ident);
+
+    quote! {
+        #(#warnings)*
 
-                        Some(#ident {
-                            #(#fidents,)*
-                            #(#opt_fidents,)*
-                            #(#each_owners,)*
-                        })
+        impl #impl_generics #ident #ty_generics #where_clause {
+            #[doc = #builder_doc]
+            pub fn builder() -> #unset_ty {
+                #builder_ident {
+                    #(#all: #init,)*
                 }
+            }
+        }
+
+        #derive_debug
+        #derive
+        #[doc = #struct_doc]
+        pub struct #builder_ident #generics #where_clause {
+            #(#all: #storage,)*
+        }
+
+        #default_impl
+
+        impl #state_impl_generics #generic_ty #where_clause {
+            #(#setters)*
+        }
+
+        impl #impl_generics #set_ty #where_clause {
+            #[doc = #build_doc]
+            #[allow(deprecated)]
+            pub fn build(self) -> #build_ret {
+                let value = #ident {
+                    #(#fidents: #values,)*
+                };
+                #finish
+            }
+
+            #call
+        }
+
+        #conversion
+
+        #to_builder
+    }
+}
+
+// The builder derives Debug, unless a field carries a `#[debug = "..."]`
+// format for `CustomDebug`: then the builder applies the same format, so that
+// it doesn't show what the struct's own Debug hides; it leaves out the fields
+// CustomDebug skips the same way. A `sensitive` field shows as `"***"`, set
+// or not.
+fn debug_impl(input: &DeriveInput, builder_ident: &Ident, fields: &[TargetField]) -> (Option<TokenStream>, Option<TokenStream>) {
+    if fields.iter().all(|f| f.plain_debug()) {
+        return (Some(quote!(#[derive(::core::fmt::Debug)])), None);
+    }
+
+    // Fields printed with `{:?}` need Debug; the bounds of formatted ones
+    // are up to the user, as with CustomDebug's `bound`. So are those of
+    // fields behind `#[cfg]`, whose types may not exist.
+    let mut generics = input.generics.clone();
+    let where_clause = generics.make_where_clause();
+    for f in fields.iter().filter(|f| f.plain_debug() && f.cfgs.is_empty()) {
+        let ty = f.held_ty();
+        where_clause.predicates.push(syn::parse_quote!(#ty: ::core::fmt::Debug));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let builder_str = builder_ident.to_string();
+    let entries = fields.iter().filter(|f| !f.debug_skip).map(|f| {
+        let (ident, cfg) = (f.ident, f.cfg());
+        let name = ident.to_string();
+        let value = match &f.debug {
+            _ if f.sensitive => quote! { &"***" },
+            None => quote! { &self.#ident },
+            // Stored as declared, so formatted as declared.
+            Some(format) if !f.optional_in_builder() => quote! { &::core::format_args!(#format, &self.#ident) },
+            Some(format) => quote! {
+                &Fmt(|f: &mut ::core::fmt::Formatter| match &self.#ident {
+                    ::core::option::Option::Some(v) => f.debug_tuple("Some").field(&::core::format_args!(#format, v)).finish(),
+                    ::core::option::Option::None => f.write_str("None"),
+                })
+            },
+        };
+        quote! {
+            #cfg
+            debug.field(#name, #value);
+        }
+    });
+
+    let finish = if fields.iter().any(|f| f.debug_skip) { quote!(finish_non_exhaustive) } else { quote!(finish) };
+
+    // Formats an unset field as `None` and a set one as `Some(...)`.
+    let fmt = fields.iter().any(|f| f.debug.is_some() && f.optional_in_builder()).then(|| quote! {
+        struct Fmt<F>(F);
+
+        impl<F: ::core::ops::Fn(&mut ::core::fmt::Formatter) -> ::core::fmt::Result> ::core::fmt::Debug for Fmt<F> {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                (self.0)(f)
+            }
+        }
+    });
+
+    let tokens = quote! {
+        impl #impl_generics ::core::fmt::Debug for #builder_ident #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                #fmt
+
+                let mut debug = f.debug_struct(#builder_str);
+                #(#entries)*
+                debug.#finish()
+            }
+        }
+    };
+    (None, Some(tokens))
+}
+
+// `#[builder(module)]`: the generated items go in a module `__foo_builder`
+// that glob-imports its parent, and the types the user names are re-exported
+// from it. The struct's field types must then be nameable from the module, so
+// not declared inside a function.
+fn module(input: &DeriveInput, container: &ContainerAttrs, builder: TokenStream) -> TokenStream {
+    let ident = &input.ident;
+    let mut name = String::new();
+    for c in ident.unraw().to_string().chars() {
+        if c.is_uppercase() && name.ends_with(|prev: char| prev.is_lowercase() || prev.is_ascii_digit()) {
+            name.push('_');
+        }
+        name.extend(c.to_lowercase());
+    }
+    let module = format_ident!("__{}_builder", name);
+
+    let builder_ident = format_ident!("{}Builder", ident);
+    let mut exports = vec![builder_ident.clone()];
+    if !container.typestate {
+        exports.push(format_ident!("{}Partial", ident));
+        if container.field_enum {
+            exports.push(format_ident!("{}Field", ident));
+        }
+        exports.push(format_ident!("{}SetError", builder_ident));
+        if container.validate.is_some() {
+            exports.push(format_ident!("{}Error", builder_ident));
+        }
+    }
+    quote! {
+        #[doc(hidden)]
+        mod #module {
+            use super::*;
+
+            #builder
+        }
+
+        pub use self::#module::{#(#exports),*};
+    }
+}
+
+// `#[builder]` on a function: the function, called by `call()` with the
+// fields of the built struct, which are its parameters.
+struct Call {
+    func: ItemFn,
+    args: Vec<Ident>,
+}
+
+impl Call {
+    fn output(&self) -> TokenStream {
+        match &self.func.sig.output {
+            ReturnType::Default => quote!(()),
+            ReturnType::Type(_, ty) => ty.to_token_stream(),
         }
+    }
+
+    // `call()` on a builder whose `build()` returns the struct `ident`, or
+    // wraps it in what `ok` constructs.
+    fn method(&self, ident: &Ident, this: &TokenStream, ret: &TokenStream, ok: Option<TokenStream>) -> TokenStream {
+        let (func, args) = (&self.func, &self.args);
+        let name = &func.sig.ident;
+        let doc = format!("Calls `{}` with the arguments set.", name);
+        let (question, value) = match ok {
+            Some(ok) => (quote!(?), quote!(#ok(#name(#(#args),*)))),
+            None => (quote!(), quote!(#name(#(#args),*))),
+        };
+        quote! {
+            #[doc = #doc]
+            #[allow(deprecated)]
+            pub fn call(#this) -> #ret {
+                #func
+                let #ident { #(#args),* } = self.build() #question;
+                #value
+            }
+        }
+    }
+}
+
+// Names what a parameter's type leaves implicit, which a field's can't: an
+// elided or `'_` lifetime becomes a lifetime `'__a0`, `'__a1`, ..., and an
+// `impl Trait` a type parameter `__I0`, ... bounded by `Trait`. Those of
+// `fn(&T)` and `Fn(&T)` are theirs, and left alone.
+#[derive(Default)]
+struct Elided {
+    lifetimes: Vec<Lifetime>,
+    params: Vec<TypeParam>,
+}
+
+impl Elided {
+    fn fresh(&mut self, span: Span) -> Lifetime {
+        let lifetime = Lifetime::new(&format!("'__a{}", self.lifetimes.len()), span);
+        self.lifetimes.push(lifetime.clone());
+        lifetime
+    }
+}
+
+impl VisitMut for Elided {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        visit_mut::visit_type_mut(self, ty);
+        if let Type::ImplTrait(impl_trait) = ty {
+            let ident = format_ident!("__I{}", self.params.len(), span = impl_trait.impl_token.span);
+            let bounds = &impl_trait.bounds;
+            self.params.push(syn::parse_quote!(#ident: #bounds));
+            *ty = syn::parse_quote!(#ident);
+        }
+    }
+
+    fn visit_type_reference_mut(&mut self, reference: &mut TypeReference) {
+        if reference.lifetime.is_none() {
+            reference.lifetime = Some(self.fresh(reference.and_token.span));
+        }
+        visit_mut::visit_type_reference_mut(self, reference);
+    }
+
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if lifetime.ident == "_" {
+            *lifetime = self.fresh(lifetime.apostrophe);
+        }
+    }
+
+    fn visit_type_bare_fn_mut(&mut self, _: &mut TypeBareFn) {}
+
+    fn visit_parenthesized_generic_arguments_mut(&mut self, _: &mut ParenthesizedGenericArguments) {}
+}
+
+// `#[builder(...)]` on `fn connect(host: String, port: u16) -> R`: the
+// parameters become the fields of a struct `ConnectArgs`, with the
+// `#[builder(...)]`s on them, and `connect()` returns its builder, whose
+// `call()` calls the function once everything is set. The attribute's
+// arguments are those of `#[builder(...)]` on the struct. Elided lifetimes
+// and `impl Trait`s of the parameters become parameters of the struct, see
+// `Elided`.
+pub fn function(args: TokenStream, item: TokenStream) -> TokenStream {
+    let mut func = match syn::parse2::<ItemFn>(item) {
+        Ok(func) => func,
+        Err(err) => return err.to_compile_error(),
+    };
+    match function_args(&args, &mut func) {
+        Ok(tokens) => tokens,
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+fn function_args(args: &TokenStream, func: &mut ItemFn) -> syn::Result<TokenStream> {
+    let sig = &mut func.sig;
+    if let Some(token) = sig.asyncness.as_ref().map(ToTokens::to_token_stream).or_else(|| sig.unsafety.as_ref().map(ToTokens::to_token_stream)) {
+        return Err(syn::Error::new_spanned(token, "`#[builder]` doesn't support `async` or `unsafe` functions"));
+    }
+    let mut fields = Vec::new();
+    let mut idents = Vec::new();
+    let mut elided = Elided::default();
+    for input in &mut sig.inputs {
+        let input = match input {
+            FnArg::Typed(input) => input,
+            FnArg::Receiver(receiver) => return Err(syn::Error::new_spanned(receiver, "`#[builder]` only supports free functions")),
+        };
+        let ident = match &*input.pat {
+            Pat::Ident(PatIdent { ident, by_ref: None, subpat: None, .. }) => ident.clone(),
+            pat => return Err(syn::Error::new_spanned(pat, "expected a parameter name")),
+        };
+        if let Some(cfg) = input.attrs.iter().find(|attr| attr.path.is_ident("cfg")) {
+            return Err(syn::Error::new_spanned(cfg, "`#[cfg]` on a parameter isn't supported with `#[builder]`"));
+        }
+        // `#[builder(...)]` and docs are for the field, not the parameter.
+        let (attrs, rest) = input.attrs.drain(..).partition::<Vec<_>, _>(|attr| attr.path.is_ident("builder") || attr.path.is_ident("doc"));
+        input.attrs = rest;
+        let mut ty = (*input.ty).clone();
+        elided.visit_type_mut(&mut ty);
+        fields.push(quote!(#(#attrs)* #ident: #ty));
+        idents.push(ident);
+    }
+
+    let (vis, name) = (&func.vis, &sig.ident);
+    let mut generics = sig.generics.clone();
+    for (i, lifetime) in elided.lifetimes.into_iter().enumerate() {
+        generics.params.insert(i, GenericParam::Lifetime(LifetimeDef::new(lifetime)));
+    }
+    generics.params.extend(elided.params.into_iter().map(GenericParam::Type));
+    let generics = &generics;
+    let (_, ty_generics, where_clause) = generics.split_for_impl();
+    let ident = format_ident!("{}Args", camel_case(name), span = name.span());
+    let builder_ident = format_ident!("{}Builder", ident);
+    let input: DeriveInput = syn::parse_quote! {
+        #[builder(#args)]
+        #vis struct #ident #generics #where_clause {
+            #(#fields,)*
+        }
+    };
+    let mut container = ContainerAttrs::from_attrs(&input.attrs)?;
+    if container.module {
+        return Err(syn::Error::new_spanned(args, "`module` isn't supported with `#[builder]` on a function"));
+    }
+
+    // The typestate builder starts out with no required field set.
+    let builder_ty = if container.typestate {
+        let data = match &input.data {
+            Data::Struct(data) => data,
+            _ => unreachable!(),
+        };
+        let targets = data.fields.iter().map(|f| TargetField::from(f, &container)).collect::<syn::Result<Vec<_>>>()?;
+        let params = generics.params.iter().map(|param| match param {
+            syn::GenericParam::Type(ty) => ty.ident.to_token_stream(),
+            syn::GenericParam::Lifetime(lt) => lt.lifetime.to_token_stream(),
+            syn::GenericParam::Const(c) => c.ident.to_token_stream(),
+        });
+        let unset = targets.iter().filter(|f| f.standard()).map(|_| quote!(()));
+        quote!(#builder_ident<#(#params,)* #(#unset,)*>)
+    } else {
+        quote!(#builder_ident #ty_generics)
+    };
+
+    let (docs, attrs) = func.attrs.drain(..).partition::<Vec<_>, _>(|attr| attr.path.is_ident("doc"));
+    func.attrs = attrs;
+    let struct_doc = format!("The arguments of `{}`, see `{}`.", name, builder_ident);
+    let struct_tokens = {
+        let DeriveInput { vis, ident, generics, data, .. } = &input;
+        let fields = match data {
+            Data::Struct(data) => data.fields.iter().map(|f| {
+                let (ident, ty) = (&f.ident, &f.ty);
+                let docs = f.attrs.iter().filter(|attr| attr.path.is_ident("doc"));
+                quote!(#(#docs)* #ident: #ty)
+            }).collect::<Vec<_>>(),
+            _ => unreachable!(),
+        };
+        let where_clause = &generics.where_clause;
+        quote! {
+            #[doc = #struct_doc]
+            #vis struct #ident #generics #where_clause {
+                #(#fields,)*
+            }
+        }
+    };
+    let turbofish = ty_generics.as_turbofish();
+    let constness = container.const_fn.then(|| quote!(const));
+    let ctor = quote! {
+        #(#docs)*
+        #vis #constness fn #name #generics() -> #builder_ty #where_clause {
+            #ident #turbofish::builder()
+        }
+    };
+
+    func.vis = syn::Visibility::Inherited;
+    container.call = Some(Call { func: func.clone(), args: idents });
+    let builder = builder(&input, &container)?;
+    if container.debug_expand {
+        attrs::debug_expand(&builder);
+    }
+    Ok(quote! {
+        #ctor
+        #struct_tokens
+        #builder
     })
 }
 
@@ -206,10 +2031,18 @@ pub fn derive(input: TokenStream) -> TokenStream {
         Ok(input) => input,
         Err(err) => return err.to_compile_error(),
     };
-    let builder = match builder(&input) {
+    let container = match ContainerAttrs::from_attrs(&input.attrs) {
+        Ok(container) => container,
+        Err(err) => return err.to_compile_error(),
+    };
+    let builder = match builder(&input, &container) {
+        Ok(builder) if container.module => module(&input, &container, builder),
         Ok(builder) => builder,
         Err(err) => return err.to_compile_error(),
     };
+    if container.debug_expand {
+        attrs::debug_expand(&builder);
+    }
 
     quote! {
         #builder
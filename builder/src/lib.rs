@@ -6,3 +6,8 @@ mod imp;
 pub fn derive(input: TokenStream) -> TokenStream {
     imp::derive(input.into()).into()
 }
+
+#[proc_macro_attribute]
+pub fn builder(args: TokenStream, input: TokenStream) -> TokenStream {
+    imp::function(args.into(), input.into()).into()
+}
@@ -0,0 +1,35 @@
+// The expansion names everything through absolute `::core` paths, so it
+// compiles in a module without the prelude and next to items that shadow
+// the names the prelude would provide.
+
+#![no_implicit_prelude]
+
+use ::derive_builder::Builder;
+use ::std::option::Option;
+use ::std::string::String;
+use ::std::vec::Vec;
+
+#[allow(dead_code, non_snake_case)]
+fn Some() {}
+#[allow(dead_code, non_upper_case_globals)]
+const None: () = ();
+#[allow(dead_code)]
+trait Default {}
+#[allow(dead_code)]
+trait Clone {}
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable(::std::string::ToString::to_string("cargo"))
+        .arg(::std::string::ToString::to_string("build"))
+        .build();
+    ::std::assert!(command.is_some());
+}
@@ -0,0 +1,18 @@
+// `#[builder(debug_expand)]` on the struct prints the generated builder to
+// stderr while compiling. Anything else there is rejected.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(debug_expand)]
+pub struct Command {
+    executable: String,
+}
+
+#[derive(Builder)]
+#[builder(verbose)]
+pub struct Other {
+    executable: String,
+}
+
+fn main() {}
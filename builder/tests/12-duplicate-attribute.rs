@@ -0,0 +1,13 @@
+// Several `#[builder(...)]` attributes on a field are merged, so an option
+// given in two of them is reported like one given twice in a single list.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(each = "arg")]
+    #[builder(each = "argument")]
+    args: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,30 @@
+// The unquoted `#[builder(each = arg)]` spelling is deprecated. It still
+// works, but produces a warning pointing at the name: a proper diagnostic on
+// nightly with the `nightly` feature, the note of a `deprecated` warning on
+// stable.
+//
+// Other non-string values remain errors.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = arg)]
+    args: Vec<String>,
+}
+
+#[derive(Builder)]
+pub struct Other {
+    #[builder(each = 1)]
+    args: Vec<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.args, vec!["build"]);
+}
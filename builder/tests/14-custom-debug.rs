@@ -0,0 +1,34 @@
+// When the struct also derives CustomDebug, the builder's Debug applies the
+// same `#[debug = "..."]` formats to the fields it holds. A required field
+// shows as `Some(...)` in that format once set and as `None` before; optional
+// and repeated fields are formatted as they are.
+
+use derive_builder::Builder;
+use derive_debug::CustomDebug;
+
+#[derive(Builder, CustomDebug)]
+pub struct Packet {
+    #[debug = "0b{:08b}"]
+    flags: u8,
+    #[debug = "{:?}!"]
+    ratio: Option<f64>,
+    #[builder(each = "byte")]
+    #[debug = "{:x?}"]
+    payload: Vec<u8>,
+    name: String,
+}
+
+fn main() {
+    let mut builder = Packet::builder();
+    builder.flags(5).byte(0xab).byte(0xcd);
+    assert_eq!(
+        format!("{:?}", builder),
+        r#"PacketBuilder { flags: Some(0b00000101), ratio: None!, payload: [ab, cd], name: None }"#,
+    );
+
+    builder.ratio(0.25).name("ping".to_owned());
+    assert_eq!(
+        format!("{:?}", builder.build().unwrap()),
+        r#"Packet { flags: 0b00000101, ratio: Some(0.25)!, payload: [ab, cd], name: "ping" }"#,
+    );
+}
@@ -0,0 +1,48 @@
+// Type parameters, lifetimes and where clauses of the struct carry over to
+// the builder, its setters and `build()`.
+
+use derive_builder::Builder;
+use derive_debug::CustomDebug;
+use std::fmt::{Debug, LowerHex};
+
+#[derive(Builder)]
+pub struct Command<'a, T: Clone, U>
+where
+    U: Clone + Debug,
+{
+    program: &'a str,
+    value: T,
+    extra: Option<U>,
+    #[builder(each = "arg")]
+    args: Vec<T>,
+}
+
+// With CustomDebug formats the builder's Debug is written out; it needs Debug
+// of the unformatted fields only.
+#[derive(Builder, CustomDebug)]
+pub struct Register<T: LowerHex + Clone> {
+    #[debug = "{:#x}"]
+    value: T,
+    name: String,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct Level(u8);
+
+fn main() {
+    let program = String::from("cargo");
+    let mut builder = Command::<Level, &str>::builder();
+    builder.program(&program).value(Level(1)).arg(Level(2)).arg(Level(3));
+
+    assert!(format!("{:?}", builder).starts_with("CommandBuilder"));
+
+    let command = builder.build().unwrap();
+    assert_eq!(command.program, "cargo");
+    assert_eq!(command.value, Level(1));
+    assert_eq!(command.extra, None);
+    assert_eq!(command.args, vec![Level(2), Level(3)]);
+
+    let mut builder = Register::<u16>::builder();
+    builder.value(0xbeef);
+    assert_eq!(format!("{:?}", builder), "RegisterBuilder { value: Some(0xbeef), name: None }");
+}
@@ -0,0 +1,38 @@
+// A field marked `#[builder(default)]` doesn't have to be set: build() fills
+// it in with `Default::default()` instead of failing. With
+// `#[builder(default = "expr")]` it uses the given expression instead.
+
+use derive_builder::Builder;
+use std::time::Duration;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(default)]
+    retries: u32,
+    #[builder(default)]
+    env: Vec<(String, String)>,
+    #[builder(default = "Duration::from_secs(30)")]
+    timeout: Duration,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.retries, 0);
+    assert!(command.env.is_empty());
+    assert_eq!(command.timeout, Duration::from_secs(30));
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .retries(3)
+        .timeout(Duration::from_secs(1))
+        .build()
+        .unwrap();
+    assert_eq!(command.retries, 3);
+    assert_eq!(command.timeout, Duration::from_secs(1));
+
+    assert!(Command::builder().retries(3).build().is_none());
+}
@@ -0,0 +1,18 @@
+// `default` is meaningless on fields that are already optional: an `Option`
+// field builds as `None` and an `each` field starts out empty.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(default)]
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+pub struct Other {
+    #[builder(each = "arg", default)]
+    args: Vec<String>,
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+// Problems with a `default = "..."` expression, whether it fails to parse or
+// to type-check, are reported at the string literal.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(default = "1 +")]
+    retries: u32,
+}
+
+#[derive(Builder)]
+pub struct Other {
+    #[builder(default = "\"thirty\"")]
+    timeout: u64,
+}
+
+fn main() {}
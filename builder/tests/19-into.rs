@@ -0,0 +1,45 @@
+// `#[builder(into)]` makes a setter take `impl Into<T>`, so that e.g. a `&str`
+// can be passed for a `String` field. On the struct it applies to every field
+// except the `each` ones, whose setters take the collection's items.
+
+use derive_builder::Builder;
+use std::path::PathBuf;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(into)]
+    executable: String,
+    #[builder(into, default)]
+    current_dir: PathBuf,
+    args: Vec<String>,
+}
+
+#[derive(Builder)]
+#[builder(into)]
+pub struct Env {
+    key: String,
+    value: Option<String>,
+    #[builder(each = "tag")]
+    tags: Vec<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo")
+        .current_dir("/tmp")
+        .args(vec!["build".to_owned()])
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.current_dir, PathBuf::from("/tmp"));
+
+    let env = Env::builder()
+        .key("RUST_LOG")
+        .value("debug")
+        .tag("log".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(env.key, "RUST_LOG");
+    assert_eq!(env.value.as_deref(), Some("debug"));
+    assert_eq!(env.tags, vec!["log"]);
+}
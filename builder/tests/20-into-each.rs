@@ -0,0 +1,12 @@
+// `into` on an `each` field is rejected: its setter takes the collection's
+// items, or anything an unknown collection can be extended with.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(each = "arg", into)]
+    args: Vec<String>,
+}
+
+fn main() {}
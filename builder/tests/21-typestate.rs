@@ -0,0 +1,40 @@
+// With `#[builder(typestate)]` the builder tracks in its type which required
+// fields have been set. The setters consume the builder and return it, and
+// `build()` returns the struct itself: it is only callable once all required
+// fields are set, so there is nothing left to fail at runtime.
+
+use derive_builder::Builder;
+
+pub struct Handle(u32);
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Command<'a, T> {
+    executable: &'a str,
+    handle: Handle,
+    #[builder(into)]
+    label: T,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(default = "3")]
+    retries: u32,
+}
+
+fn main() {
+    // Required setters may come in any order, and be called again.
+    let command = Command::<String>::builder()
+        .handle(Handle(1))
+        .arg("build".to_owned())
+        .executable("cargo")
+        .label("x")
+        .handle(Handle(7))
+        .build();
+
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.handle.0, 7);
+    assert_eq!(command.label, "x");
+    assert_eq!(command.args, vec!["build"]);
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.retries, 3);
+}
@@ -0,0 +1,37 @@
+// `#[builder(owned)]` makes the setters take `mut self` and return `Self`,
+// and `build(self)` consume the builder. The whole chain can then be one
+// expression.
+
+use derive_builder::Builder;
+
+#[derive(Debug)]
+pub struct Handle(u32);
+
+#[derive(Builder)]
+#[builder(owned)]
+pub struct Command {
+    executable: String,
+    handle: Handle,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(default)]
+    retries: u32,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .handle(Handle(3))
+        .arg("build".to_owned())
+        .arg("--release".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.handle.0, 3);
+    assert_eq!(command.args, vec!["build", "--release"]);
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.retries, 0);
+
+    assert!(Command::builder().handle(Handle(3)).build().is_none());
+}
@@ -0,0 +1,55 @@
+// `#[builder(validate = "path")]` runs `path` on the assembled struct before
+// `build()` returns it, for invariants across fields. `build()` then returns a
+// `Result` whose error is `FooBuilderError`: either a required field is
+// missing or the validation function rejected the value. Its error type is
+// `&'static str` unless given by `error = "..."`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(validate = "Range::check", error = "String")]
+pub struct Range {
+    min: u32,
+    max: u32,
+}
+
+impl Range {
+    fn check(&self) -> Result<(), String> {
+        if self.min <= self.max {
+            Ok(())
+        } else {
+            Err(format!("{} > {}", self.min, self.max))
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Empty;
+
+fn non_empty(name: &Name) -> Result<(), Empty> {
+    if name.value.is_empty() { Err(Empty) } else { Ok(()) }
+}
+
+#[derive(Builder)]
+#[builder(typestate, validate = "non_empty", error = "Empty")]
+pub struct Name {
+    value: String,
+}
+
+fn main() {
+    let range = Range::builder().min(1).max(2).build().unwrap();
+    assert_eq!((range.min, range.max), (1, 2));
+
+    match Range::builder().min(3).max(2).build() {
+        Err(RangeBuilderError::Invalid(msg)) => assert_eq!(msg, "3 > 2"),
+        _ => unreachable!(),
+    }
+    match Range::builder().min(3).build() {
+        Err(RangeBuilderError::Missing(field)) => assert_eq!(field, "max"),
+        _ => unreachable!(),
+    }
+
+    // A typestate builder can't miss a field, so only the validation can fail.
+    assert_eq!(Name::builder().value(String::new()).build().err(), Some(Empty));
+    assert!(Name::builder().value("x".to_owned()).build().is_ok());
+}
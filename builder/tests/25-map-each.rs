@@ -0,0 +1,34 @@
+// The one-at-a-time setter of a `HashMap` or `BTreeMap` field takes the key
+// and the value as two arguments.
+
+use derive_builder::Builder;
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Builder)]
+pub struct Request {
+    #[builder(each = "header")]
+    headers: HashMap<String, String>,
+    #[builder(each = "param")]
+    params: BTreeMap<&'static str, u32>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Env {
+    #[builder(each = "var")]
+    vars: BTreeMap<String, String>,
+}
+
+fn main() {
+    let request = Request::builder()
+        .header("Accept".to_owned(), "*/*".to_owned())
+        .param("page", 2)
+        .param("limit", 10)
+        .build()
+        .unwrap();
+    assert_eq!(request.headers["Accept"], "*/*");
+    assert_eq!(request.params.into_iter().collect::<Vec<_>>(), [("limit", 10), ("page", 2)]);
+
+    let env = Env::builder().var("HOME".to_owned(), "/root".to_owned()).build();
+    assert_eq!(env.vars["HOME"], "/root");
+}
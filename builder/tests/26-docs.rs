@@ -0,0 +1,21 @@
+//! The doc comments of a field are repeated on its setters, so they show up
+//! where the setter is used. The other public items of the builder have docs
+//! of their own, so a crate using `missing_docs` can derive Builder.
+
+#![deny(missing_docs)]
+
+use derive_builder::Builder;
+
+/// A command to run.
+#[derive(Builder)]
+pub struct Command {
+    /// The program to run.
+    pub executable: String,
+    /// Arguments, one at a time.
+    #[builder(each = "arg")]
+    pub args: Vec<String>,
+}
+
+fn main() {
+    Command::builder().executable("cargo".to_owned()).arg("build".to_owned());
+}
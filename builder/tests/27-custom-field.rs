@@ -0,0 +1,30 @@
+// `#[builder(field(ty = "...", build = "..."))]` lets the builder hold some
+// other state for a field: the setter takes that type, the builder starts from
+// its default, and build() evaluates the expression, which can read the
+// builder as `self`, to produce the field. In build() `?` returns `None`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Server {
+    host: String,
+    #[builder(field(ty = "String", build = "self.port.parse().ok()?"))]
+    port: u16,
+    #[builder(into, field(ty = "Vec<&'static str>", build = "self.aliases.join(\",\")"))]
+    aliases: String,
+}
+
+fn main() {
+    let server = Server::builder()
+        .host("localhost".to_owned())
+        .port("8080".to_owned())
+        .aliases(["a", "b"])
+        .build()
+        .unwrap();
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.aliases, "a,b");
+
+    let mut builder = Server::builder();
+    builder.host("localhost".to_owned()).port("http".to_owned());
+    assert!(builder.build().is_none());
+}
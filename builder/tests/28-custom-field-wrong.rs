@@ -0,0 +1,18 @@
+// `field(...)` needs both `ty` and `build`, and replaces how the field is
+// stored, which leaves nothing for `each` or `default` to do.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Server {
+    #[builder(field(ty = "String"))]
+    port: u16,
+}
+
+#[derive(Builder)]
+pub struct Other {
+    #[builder(default, field(ty = "String", build = "self.port.parse().ok()?"))]
+    port: u16,
+}
+
+fn main() {}
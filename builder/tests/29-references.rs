@@ -0,0 +1,54 @@
+// Structs that borrow keep their lifetimes in the builder; setters take the
+// borrowed types as declared.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Query<'a> {
+    table: &'a str,
+    columns: &'a [&'a str],
+    filter: Option<&'a str>,
+    #[builder(each = "join")]
+    joins: Vec<&'a str>,
+    #[builder(default)]
+    alias: &'a str,
+}
+
+#[derive(Builder)]
+#[builder(owned)]
+pub struct Writer<'a> {
+    out: &'a mut Vec<u8>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Lookup<'k, 'v: 'k> {
+    key: &'k str,
+    value: &'v str,
+}
+
+fn main() {
+    let table = String::from("users");
+    let columns = ["id", "name"];
+    let query = Query::builder()
+        .table(&table)
+        .columns(&columns)
+        .filter("id > 1")
+        .join("roles")
+        .build()
+        .unwrap();
+    assert_eq!(query.table, "users");
+    assert_eq!(query.columns, ["id", "name"]);
+    assert_eq!(query.filter, Some("id > 1"));
+    assert_eq!(query.joins, vec!["roles"]);
+    assert_eq!(query.alias, "");
+
+    let mut buf = Vec::new();
+    let writer = Writer::builder().out(&mut buf).build().unwrap();
+    writer.out.push(1);
+    assert_eq!(buf, [1]);
+
+    let value = String::from("v");
+    let lookup = Lookup::builder().key("k").value(&value).build();
+    assert_eq!((lookup.key, lookup.value), ("k", "v"));
+}
@@ -0,0 +1,84 @@
+// The expansion names nothing from `std`, so the derive works in `no_std`
+// crates. Linking `std` under another name keeps the binary runnable while
+// leaving `::std` unresolvable. Collections for `each` come from `alloc`; the
+// builder only needs them to be `Default` and `Extend`, and `as_ref` fields
+// only to `Deref` to what they are made from.
+
+#![no_std]
+
+extern crate alloc;
+extern crate std as host;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(validate = "Frame::check")]
+pub struct Frame {
+    id: u16,
+    #[builder(each = "byte")]
+    payload: Vec<u8>,
+    #[builder(default)]
+    retries: u8,
+    priority: Option<u8>,
+}
+
+impl Frame {
+    fn check(&self) -> Result<(), &'static str> {
+        if self.payload.len() <= 8 { Ok(()) } else { Err("payload longer than 8 bytes") }
+    }
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Pin {
+    number: u8,
+    output: Option<bool>,
+}
+
+// Named like `std`'s, but only what it derefs to matters.
+#[derive(Debug)]
+pub struct PathBuf(String);
+
+impl core::ops::Deref for PathBuf {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for PathBuf {
+    fn from(path: &str) -> Self {
+        PathBuf(path.into())
+    }
+}
+
+#[derive(Builder)]
+pub struct Label {
+    #[builder(as_ref)]
+    text: String,
+    #[builder(as_ref)]
+    glyphs: Vec<u8>,
+    #[builder(as_ref)]
+    font: PathBuf,
+}
+
+fn main() {
+    let frame = Frame::builder().id(7).byte(1).byte(2).build().unwrap();
+    host::assert_eq!((frame.id, frame.payload, frame.retries, frame.priority), (7, alloc::vec![1, 2], 0, None));
+
+    let mut builder = Frame::builder();
+    builder.id(1);
+    for byte in 0..9 {
+        builder.byte(byte);
+    }
+    host::assert!(host::matches!(builder.build(), Err(FrameBuilderError::Invalid("payload longer than 8 bytes"))));
+
+    let pin = Pin::builder().number(13).build();
+    host::assert_eq!((pin.number, pin.output), (13, None));
+
+    let label = Label::builder().text("ok").glyphs(b"ok").font("mono").build().unwrap();
+    host::assert_eq!((label.text.as_str(), label.glyphs.as_slice(), &*label.font), ("ok", &b"ok"[..], "mono"));
+}
@@ -0,0 +1,34 @@
+// `build_partial()` borrows what the builder holds so far as a `FooPartial`
+// with one public field per struct field: `Option<T>` for the ones set as a
+// whole, the collection for `each` ones. Its `missing()` names the required
+// fields not set yet, so a caller can report them all instead of getting
+// `None` from `build()`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command<T: Default> {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    env: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(default)]
+    priority: T,
+}
+
+fn main() {
+    let mut builder = Command::<u8>::builder();
+    builder.arg("build".to_owned()).current_dir("..".to_owned());
+
+    let partial = builder.build_partial();
+    assert_eq!(partial.executable, &None);
+    assert_eq!(partial.args, &["build"]);
+    assert_eq!(partial.current_dir.as_deref(), Some(".."));
+    assert_eq!(partial.priority, &None);
+    assert_eq!(partial.missing().collect::<Vec<_>>(), ["executable", "env"]);
+
+    builder.executable("cargo".to_owned()).env(vec![]);
+    assert_eq!(builder.build_partial().missing().count(), 0);
+    assert!(builder.build().is_some());
+}
@@ -0,0 +1,46 @@
+// `#[builder(derive(...))]` adds traits to the builder besides `Debug`. A
+// `Clone` builder can be forked after setting what several values have in
+// common. `Default` is the same as `Foo::builder()`, and needs no bound on
+// the struct's type parameters.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(derive(Clone, Default))]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+}
+
+pub struct NotDefault;
+
+#[derive(Builder)]
+#[builder(owned, derive(Default))]
+pub struct Wrapper<T> {
+    value: T,
+}
+
+#[derive(Builder)]
+#[builder(typestate, derive(Clone, Default))]
+pub struct Pair {
+    left: u8,
+    right: u8,
+}
+
+fn main() {
+    let mut cargo = CommandBuilder::default();
+    cargo.executable("cargo".to_owned()).arg("--locked".to_owned());
+    let build = cargo.clone().arg("build".to_owned()).build().unwrap();
+    let test = cargo.arg("test".to_owned()).build().unwrap();
+    assert_eq!(build.args, ["--locked", "build"]);
+    assert_eq!(test.args, ["--locked", "test"]);
+
+    let wrapper = WrapperBuilder::default().value(NotDefault).build();
+    assert!(wrapper.is_some());
+
+    let left = PairBuilder::default().left(1);
+    let pair = left.clone().right(2).build();
+    assert_eq!((pair.left, pair.right), (1, 2));
+    assert_eq!(left.right(3).build().right, 3);
+}
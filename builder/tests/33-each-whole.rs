@@ -0,0 +1,39 @@
+// An `each` field whose one-at-a-time setter is named differently from the
+// field keeps the setter taking the whole collection under the field's name.
+// Items added later extend what it set.
+
+use derive_builder::Builder;
+use std::collections::HashMap;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(each = "env")]
+    envs: HashMap<String, String>,
+    #[builder(each = "path")]
+    path: Vec<String>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Batch {
+    #[builder(each = "job")]
+    jobs: Vec<u32>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .args(vec!["build".to_owned()])
+        .arg("--release".to_owned())
+        .envs(HashMap::from([("A".to_owned(), "1".to_owned())]))
+        .path("/bin".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.args, ["build", "--release"]);
+    assert_eq!(command.envs["A"], "1");
+    assert_eq!(command.path, ["/bin"]);
+
+    let batch = Batch::builder().jobs(vec![1, 2]).job(3).build();
+    assert_eq!(batch.jobs, [1, 2, 3]);
+}
@@ -0,0 +1,31 @@
+// Optional fields are recognized however `Option` is spelled out. An alias
+// can't be seen through, so a field of an `Option` alias says so with
+// `#[builder(optional)]`; its type's one type argument is what the setter
+// takes.
+
+use derive_builder::Builder;
+
+type Maybe<T> = Option<T>;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    current_dir: std::option::Option<String>,
+    user: ::core::option::Option<String>,
+    group: std::option::Option<u32>,
+    #[builder(optional)]
+    shell: Maybe<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .user("root".to_owned())
+        .shell("sh".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.user.as_deref(), Some("root"));
+    assert_eq!(command.group, None);
+    assert_eq!(command.shell.as_deref(), Some("sh"));
+}
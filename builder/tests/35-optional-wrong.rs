@@ -0,0 +1,11 @@
+use derive_builder::Builder;
+
+type Port = Option<u16>;
+
+#[derive(Builder)]
+pub struct Server {
+    #[builder(optional)]
+    port: Port,
+}
+
+fn main() {}
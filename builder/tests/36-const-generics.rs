@@ -0,0 +1,33 @@
+// Const parameters of the struct carry over to the builder like type
+// parameters, defaults included.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(derive(Clone, Default))]
+pub struct Buf<const N: usize> {
+    data: [u8; N],
+    label: String,
+    #[builder(each = "tag")]
+    tags: Vec<[char; N]>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Matrix<T: Copy, const R: usize, const C: usize = 2> {
+    cells: [[T; C]; R],
+    name: Option<String>,
+}
+
+fn main() {
+    let buf = BufBuilder::<4>::default().data([1, 2, 3, 4]).label("x".to_owned()).tag(['a'; 4]).build().unwrap();
+    assert_eq!(buf.data, [1, 2, 3, 4]);
+    assert_eq!(buf.tags, [['a'; 4]]);
+
+    let builder = Buf::<2>::builder();
+    assert_eq!(builder.build_partial().missing().collect::<Vec<_>>(), ["data", "label"]);
+
+    let matrix: Matrix<u8, 1> = Matrix::builder().cells([[1, 2]]).build();
+    assert_eq!(matrix.cells, [[1, 2]]);
+    assert_eq!(matrix.name, None);
+}
@@ -0,0 +1,53 @@
+// `Foo` converts from its builder with `TryFrom`, by value and, unless the
+// builder is owned, by `&mut`. The error is `()` like `build()`'s `None`, or
+// `FooBuilderError` with `validate`. A typestate builder with every field set
+// can't fail, so `Foo` is `From` it.
+
+use derive_builder::Builder;
+use std::convert::{TryFrom, TryInto};
+
+#[derive(Builder, Debug)]
+pub struct Command {
+    executable: String,
+    current_dir: Option<String>,
+}
+
+#[derive(Builder, Debug)]
+#[builder(owned, validate = "Port::check")]
+pub struct Port {
+    number: u16,
+}
+
+impl Port {
+    fn check(&self) -> Result<(), &'static str> {
+        if self.number == 0 { Err("port 0") } else { Ok(()) }
+    }
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Pair<T> {
+    left: T,
+    right: T,
+}
+
+fn convert<B, T: TryFrom<B>>(builder: B) -> Option<T> {
+    T::try_from(builder).ok()
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    assert_eq!(Command::try_from(&mut builder).unwrap_err(), ());
+    builder.executable("cargo".to_owned());
+    let command: Command = (&mut builder).try_into().unwrap();
+    assert_eq!(command.executable, "cargo");
+    builder.executable("rustc".to_owned());
+    assert!(convert::<_, Command>(builder).is_some());
+
+    assert!(matches!(Port::try_from(Port::builder()), Err(PortBuilderError::Missing("number"))));
+    assert!(matches!(Port::try_from(Port::builder().number(0)), Err(PortBuilderError::Invalid("port 0"))));
+    assert_eq!(Port::try_from(Port::builder().number(80)).unwrap().number, 80);
+
+    let pair: Pair<u8> = Pair::builder().left(1).right(2).into();
+    assert_eq!((pair.left, pair.right), (1, 2));
+}
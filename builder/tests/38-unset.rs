@@ -0,0 +1,52 @@
+// `unset_field()` puts one field back to how `Foo::builder()` started it
+// out, and `clear()` does so for all of them, so a builder can be reused. A
+// field named `clear` keeps its setter, and the builder has no `clear()`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(default = "7")]
+    priority: u8,
+}
+
+#[derive(Builder)]
+#[builder(owned)]
+pub struct Port {
+    number: u16,
+}
+
+#[derive(Builder)]
+pub struct Filter {
+    clear: bool,
+    level: u8,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned()).arg("build".to_owned()).current_dir("..".to_owned()).priority(1);
+
+    builder.unset_args().unset_current_dir().unset_priority();
+    let command = builder.build().unwrap();
+    assert!(command.args.is_empty());
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.priority, 7);
+
+    builder.unset_executable();
+    assert!(builder.build().is_none());
+
+    builder.executable("rustc".to_owned()).arg("-V".to_owned());
+    assert!(builder.clear().build().is_none());
+    assert!(builder.build_partial().args.is_empty());
+
+    assert!(Port::builder().number(80).clear().build().is_none());
+    assert!(Port::builder().number(80).unset_number().build().is_none());
+
+    let filter = Filter::builder().clear(true).level(3).unset_clear().clear(false).build().unwrap();
+    assert!(!filter.clear);
+    assert_eq!(filter.level, 3);
+}
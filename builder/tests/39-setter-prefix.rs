@@ -0,0 +1,37 @@
+// `#[builder(setter_prefix = "with_")]` puts a prefix before the name of
+// every setter, `each` ones included. Raw identifiers lose their `r#`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(setter_prefix = "with_")]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(each = "env")]
+    env: Vec<String>,
+    r#type: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(typestate, setter_prefix = "set_")]
+pub struct Port {
+    number: u16,
+}
+
+fn main() {
+    let command = Command::builder()
+        .with_executable("cargo".to_owned())
+        .with_args(vec!["build".to_owned()])
+        .with_arg("--release".to_owned())
+        .with_env("A=1".to_owned())
+        .with_type("bin".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.args, ["build", "--release"]);
+    assert_eq!(command.env, ["A=1"]);
+    assert_eq!(command.r#type.as_deref(), Some("bin"));
+
+    assert_eq!(Port::builder().set_number(80).build().number, 80);
+}
@@ -0,0 +1,30 @@
+// `get_field()` shows what the builder holds so far: `Option<&T>` for fields
+// set as a whole, the collection for `each` ones.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(default)]
+    jobs: u8,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned()).arg("build".to_owned());
+
+    assert_eq!(builder.get_executable().map(String::as_str), Some("cargo"));
+    assert_eq!(builder.get_args(), &["build"]);
+    assert_eq!(builder.get_current_dir(), None);
+    assert_eq!(builder.get_jobs(), None);
+
+    // A default that depends on what else was set.
+    if builder.get_args().iter().any(|arg| arg == "build") && builder.get_jobs().is_none() {
+        builder.jobs(4);
+    }
+    assert_eq!(builder.build().unwrap().jobs, 4);
+}
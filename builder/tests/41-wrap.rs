@@ -0,0 +1,43 @@
+// `#[builder(wrap)]` on a `Box`, `Rc` or `Arc` field makes the setter take
+// what it points to and do the wrapping. Optional fields and `into` work as
+// usual around it.
+
+use derive_builder::Builder;
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct Handler {
+    name: String,
+}
+
+#[derive(Builder)]
+pub struct Server {
+    #[builder(wrap)]
+    handler: Arc<Handler>,
+    #[builder(wrap, into)]
+    banner: Box<String>,
+    #[builder(wrap)]
+    fallback: Option<std::rc::Rc<Handler>>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Node {
+    #[builder(wrap)]
+    value: Rc<u32>,
+}
+
+fn main() {
+    let server = Server::builder()
+        .handler(Handler { name: "main".to_owned() })
+        .banner("hello")
+        .fallback(Handler { name: "404".to_owned() })
+        .build()
+        .unwrap();
+    assert_eq!(server.handler.name, "main");
+    assert_eq!(*server.banner, "hello");
+    assert_eq!(server.fallback.unwrap().name, "404");
+
+    assert_eq!(*Node::builder().value(1).build().value, 1);
+}
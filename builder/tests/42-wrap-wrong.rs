@@ -0,0 +1,9 @@
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Server {
+    #[builder(wrap)]
+    handlers: Vec<String>,
+}
+
+fn main() {}
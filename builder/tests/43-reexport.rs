@@ -0,0 +1,34 @@
+// The expansion names nothing but `::core`, never this crate, so the derive
+// works from a re-export without telling it where it was re-exported from.
+// The one other crate it can name, serde, is found where
+// `#[builder(serde(crate = "..."))]` says, so that works from a facade too.
+
+mod facade {
+    pub mod builder {
+        pub use derive_builder::Builder;
+    }
+
+    pub use serde;
+}
+
+#[derive(facade::builder::Builder)]
+#[builder(validate = "Command::check", derive(Clone, Default), serde(crate = "facade::serde"))]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+}
+
+impl Command {
+    fn check(&self) -> Result<(), &'static str> {
+        Ok(())
+    }
+}
+
+fn main() {
+    let command = CommandBuilder::default().executable("cargo".to_owned()).arg("build".to_owned()).build().unwrap();
+    assert_eq!(command.args, ["build"]);
+
+    let mut builder: CommandBuilder = serde_json::from_str(r#"{"executable": "rustc"}"#).unwrap();
+    assert_eq!(builder.build().unwrap().executable, "rustc");
+}
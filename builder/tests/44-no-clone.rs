@@ -0,0 +1,35 @@
+// `build()` takes the values out of a borrowed builder instead of cloning
+// them, so fields needn't be `Clone`, and the builder is empty afterwards. A
+// `build()` that fails for a missing field takes nothing.
+
+use derive_builder::Builder;
+use std::fs::File;
+
+#[derive(Builder)]
+pub struct Job<'a> {
+    log: File,
+    out: &'a mut Vec<u8>,
+    #[builder(each = "input")]
+    inputs: Vec<File>,
+    lock: Option<File>,
+}
+
+fn main() {
+    let mut out = Vec::new();
+    let mut builder = Job::builder();
+    builder.out(&mut out).input(File::open("Cargo.toml").unwrap());
+    assert!(builder.build().is_none());
+    assert!(builder.get_out().is_some());
+    assert_eq!(builder.get_inputs().len(), 1);
+
+    builder.log(File::open("Cargo.toml").unwrap()).lock(File::open("Cargo.toml").unwrap());
+    let job = builder.build().unwrap();
+    assert_eq!(job.inputs.len(), 1);
+    assert!(job.lock.is_some());
+    job.out.push(1);
+
+    assert_eq!(builder.build_partial().missing().collect::<Vec<_>>(), ["log", "out"]);
+    assert!(builder.get_inputs().is_empty());
+    assert!(builder.get_lock().is_none());
+    assert_eq!(out, [1]);
+}
@@ -0,0 +1,33 @@
+// `#[cfg]` on a field is repeated on everything generated for it: the
+// builder's field, its setters and accessors, and its part of `build()`. A
+// field configured out doesn't exist in the builder either, so its type
+// needn't exist.
+
+use derive_builder::Builder;
+use derive_debug::CustomDebug;
+
+#[derive(Builder, CustomDebug)]
+pub struct Server {
+    #[debug = "{:#x}"]
+    port: u16,
+    #[cfg(any())]
+    tls: TlsConfig,
+    #[cfg(any())]
+    #[builder(each = "cert")]
+    certs: Vec<Cert>,
+    #[cfg(all())]
+    #[builder(default = "4")]
+    workers: u8,
+    #[cfg(all())]
+    name: String,
+}
+
+fn main() {
+    let mut builder = Server::builder();
+    builder.port(443);
+    assert_eq!(builder.build_partial().missing().collect::<Vec<_>>(), ["name"]);
+    assert_eq!(format!("{:?}", builder), "ServerBuilder { port: Some(0x1bb), workers: None, name: None }");
+
+    let server = builder.name("web".to_owned()).build().unwrap();
+    assert_eq!((server.port, server.workers, server.name.as_str()), (443, 4, "web"));
+}
@@ -0,0 +1,21 @@
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(typstate)]
+pub struct Command {
+    executable: String,
+}
+
+#[derive(Builder)]
+pub struct Server {
+    #[builder(defualt)]
+    port: u16,
+}
+
+#[derive(Builder)]
+pub struct Client {
+    #[builder(field(ty = "u8", bulid = "0"))]
+    retries: u8,
+}
+
+fn main() {}
@@ -0,0 +1,47 @@
+// `#[builder(serde)]` derives serde's `Serialize` and `Deserialize` for the
+// builder, so part of a configuration can be loaded and the rest set in
+// code. Fields absent from the input are unset, as in `Foo::builder()`. The
+// crate using it depends on serde, or names where serde is re-exported with
+// `#[builder(serde(crate = "..."))]`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(serde)]
+pub struct Command<T: Default> {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(default)]
+    priority: T,
+}
+
+mod facade {
+    pub use serde as ser;
+}
+
+#[derive(Builder)]
+#[builder(serde(crate = "facade::ser"))]
+pub struct Port {
+    number: u16,
+}
+
+fn main() {
+    let mut builder: CommandBuilder<u8> = serde_json::from_str(r#"{"args": ["build"], "current_dir": ".."}"#).unwrap();
+    assert!(builder.get_executable().is_none());
+
+    builder.executable("cargo".to_owned()).arg("--release".to_owned());
+    assert_eq!(
+        serde_json::to_value(&builder).unwrap(),
+        serde_json::json!({"executable": "cargo", "args": ["build", "--release"], "current_dir": "..", "priority": null}),
+    );
+
+    let command = builder.build().unwrap();
+    assert_eq!(command.args, ["build", "--release"]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+    assert_eq!(command.priority, 0);
+
+    let mut port: PortBuilder = serde_json::from_str(r#"{"number": 80}"#).unwrap();
+    assert_eq!(port.build().unwrap().number, 80);
+}
@@ -0,0 +1,41 @@
+// A bare `#[builder(each)]` names the one-at-a-time setter after the field:
+// a trailing `ies` becomes `y` and a trailing `s` is dropped, except after
+// `s` or `u`. When that doesn't apply or gives a keyword, it is `push_field`.
+// The whole-collection setter stays under the field's name.
+
+use derive_builder::Builder;
+use std::collections::BTreeMap;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(each)]
+    args: Vec<String>,
+    #[builder(each)]
+    envs: BTreeMap<String, String>,
+    #[builder(each)]
+    path: Vec<String>,
+    #[builder(each)]
+    entries: Vec<String>,
+    #[builder(each)]
+    status: Vec<String>,
+    #[builder(each)]
+    fns: Vec<String>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .arg("build".to_owned())
+        .env("A".to_owned(), "1".to_owned())
+        .push_path("/bin".to_owned())
+        .entry("b".to_owned())
+        .push_status("ok".to_owned())
+        .push_fns("main".to_owned())
+        .build()
+        .unwrap();
+    assert_eq!(command.args, ["build"]);
+    assert_eq!(command.envs["A"], "1");
+    assert_eq!(command.path, ["/bin"]);
+    assert_eq!(command.entries, ["b"]);
+    assert_eq!(command.status, ["ok"]);
+    assert_eq!(command.fns, ["main"]);
+}
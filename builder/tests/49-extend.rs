@@ -0,0 +1,35 @@
+// An `each` field also gets `extend_field()`, adding every item of an
+// iterator, key-value pairs for maps.
+
+use derive_builder::Builder;
+use std::collections::HashMap;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(each = "env")]
+    env: HashMap<String, String>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Batch {
+    name: String,
+    #[builder(each)]
+    jobs: Vec<u32>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .arg("build".to_owned())
+        .extend_args(["--locked", "--release"].iter().map(|arg| arg.to_string()))
+        .extend_env(vec![("A".to_owned(), "1".to_owned())])
+        .build()
+        .unwrap();
+    assert_eq!(command.args, ["build", "--locked", "--release"]);
+    assert_eq!(command.env["A"], "1");
+
+    let batch = Batch::builder().extend_jobs(1..4).job(4).name("b".to_owned()).build();
+    assert_eq!(batch.jobs, [1, 2, 3, 4]);
+}
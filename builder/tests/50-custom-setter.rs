@@ -0,0 +1,34 @@
+// `#[builder(setter(custom))]` keeps the field in the builder but generates
+// none of its setters, so they can be written by hand. The builder's field
+// has the struct field's name and holds an `Option` unless it is an `each`
+// field.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    #[builder(setter(custom))]
+    executable: String,
+    #[builder(each = "arg", setter(custom))]
+    args: Vec<String>,
+    current_dir: Option<String>,
+}
+
+impl CommandBuilder {
+    pub fn executable(&mut self, path: &str) -> &mut Self {
+        self.executable = Some(path.trim().to_owned());
+        self
+    }
+
+    pub fn arg(&mut self, arg: impl ToString) -> &mut Self {
+        self.args.push(arg.to_string());
+        self
+    }
+}
+
+fn main() {
+    let command = Command::builder().executable(" cargo ").arg(1).current_dir("..".to_owned()).build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, ["1"]);
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+}
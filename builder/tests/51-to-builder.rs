@@ -0,0 +1,44 @@
+// `From<Command>` for the builder sets every field from an existing value,
+// and `Command::to_builder()` does the same from a clone of it, to build a
+// modified copy. Only `to_builder()` needs the struct to be `Clone`.
+
+use derive_builder::Builder;
+
+#[derive(Builder, Clone, Debug, PartialEq)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[builder(default)]
+    env: Vec<String>,
+    current_dir: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Point {
+    x: i32,
+    y: i32,
+    label: Option<&'static str>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .current_dir("..".to_owned())
+        .build()
+        .unwrap();
+
+    let release = command.to_builder().arg("--release".to_owned()).build().unwrap();
+    assert_eq!(release.args, ["build", "--release"]);
+    assert_eq!(release.current_dir.as_deref(), Some(".."));
+    assert_eq!(command.args, ["build"]);
+
+    let same = CommandBuilder::from(command.clone()).build().unwrap();
+    assert_eq!(same, command);
+
+    let point = Point::builder().x(1).y(2).label("a").build();
+    let moved = PointBuilder::from(point).x(3).build();
+    assert_eq!((moved.x, moved.y, moved.label), (3, 2, Some("a")));
+}
@@ -0,0 +1,37 @@
+// An `Option<Vec<T>>` field can have `each` too. The builder holds the
+// `Option`: the first item pushed makes it `Some`, and if none is, build()
+// leaves the field `None`, also after extending it with no items. The
+// whole-value setter takes the `Vec`.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Option<Vec<String>>,
+    #[builder(each = "env")]
+    envs: Option<Vec<String>>,
+    #[builder(each = "path")]
+    paths: Option<Vec<String>>,
+}
+
+fn main() {
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .arg("build".to_owned())
+        .arg("--release".to_owned())
+        .extend_envs(Vec::<String>::new())
+        .extend_args(Some("-q".to_owned()))
+        .paths(vec!["a".to_owned()])
+        .path("b".to_owned())
+        .build()
+        .unwrap();
+
+    assert_eq!(command.args.as_deref(), Some(&["build".to_owned(), "--release".to_owned(), "-q".to_owned()][..]));
+    assert!(command.envs.is_none());
+    assert_eq!(command.paths.unwrap(), ["a", "b"]);
+
+    let command = Command::builder().executable("cargo".to_owned()).build().unwrap();
+    assert!(command.args.is_none());
+}
@@ -0,0 +1,46 @@
+// `#[builder(module)]` generates everything in a hidden module of its own,
+// `__command_builder` here, and re-exports only the types the user names:
+// the builder, its `Partial` and, with `validate`, its error type. The module
+// sees everything its parent does, private items included.
+
+use derive_builder::Builder;
+
+#[derive(Debug)]
+struct Program(&'static str);
+
+fn check(command: &Command) -> Result<(), &'static str> {
+    if command.executable.0.is_empty() {
+        return Err("empty executable");
+    }
+    Ok(())
+}
+
+#[derive(Builder)]
+#[builder(module, validate = "check")]
+pub struct Command {
+    executable: Program,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+}
+
+#[derive(Builder)]
+#[builder(module)]
+pub struct HttpRequest {
+    url: String,
+}
+
+fn main() {
+    let builder: CommandBuilder = Command::builder();
+    let partial: CommandPartial = builder.build_partial();
+    assert_eq!(partial.missing().collect::<Vec<_>>(), ["executable"]);
+
+    let command = Command::builder().executable(Program("cargo")).arg("build".to_owned()).build().unwrap();
+    assert_eq!(command.executable.0, "cargo");
+    assert_eq!(command.args, ["build"]);
+
+    let error: CommandBuilderError = Command::builder().executable(Program("")).build().err().unwrap();
+    assert!(matches!(error, CommandBuilderError::Invalid("empty executable")));
+
+    let _: HttpRequestBuilder = HttpRequest::builder();
+    let _ = __http_request_builder::HttpRequestBuilder::url;
+}
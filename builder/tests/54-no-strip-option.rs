@@ -0,0 +1,30 @@
+// `#[builder(no_strip_option)]` keeps an `Option<T>` field from being
+// optional in the builder: its setter takes the `Option<T>` itself, and
+// build() fails until it is set, so `None` has to be chosen explicitly.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(no_strip_option)]
+    current_dir: Option<String>,
+    timeout: Option<u32>,
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    builder.executable("cargo".to_owned());
+    assert!(builder.build().is_none());
+
+    let command = Command::builder().executable("cargo".to_owned()).current_dir(None).build().unwrap();
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.timeout, None);
+
+    let command = Command::builder()
+        .executable("cargo".to_owned())
+        .current_dir(Some("..".to_owned()))
+        .build()
+        .unwrap();
+    assert_eq!(command.current_dir.as_deref(), Some(".."));
+}
@@ -0,0 +1,27 @@
+// `#[builder(sensitive)]` keeps a field's value out of the builder's Debug,
+// which shows `"***"` in its place whether it is set or not. The field's type
+// doesn't need to be Debug.
+
+use derive_builder::Builder;
+
+pub struct Password(String);
+
+#[derive(Builder)]
+pub struct Login {
+    user: String,
+    #[builder(sensitive)]
+    password: Password,
+    #[builder(sensitive)]
+    token: Option<String>,
+}
+
+fn main() {
+    let mut builder = Login::builder();
+    assert_eq!(format!("{:?}", builder), r#"LoginBuilder { user: None, password: "***", token: "***" }"#);
+
+    builder.user("admin".to_owned()).password(Password("hunter2".to_owned())).token("secret".to_owned());
+    assert_eq!(format!("{:?}", builder), r#"LoginBuilder { user: Some("admin"), password: "***", token: "***" }"#);
+
+    let login = builder.build().unwrap();
+    assert_eq!(login.password.0, "hunter2");
+}
@@ -0,0 +1,33 @@
+// `#[builder(alias = "...")]`, which can be repeated, adds setters of other
+// names that set the same field, to keep old names working after a rename.
+// They are prefixed like the setter, and the typestate builder has them too.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Server {
+    #[builder(alias = "addr", alias = "address")]
+    host: String,
+    #[builder(alias = "current_dir")]
+    root: Option<String>,
+}
+
+#[derive(Builder)]
+#[builder(typestate, setter_prefix = "with_")]
+pub struct Point {
+    #[builder(alias = "left")]
+    x: i32,
+    y: i32,
+}
+
+fn main() {
+    let server = Server::builder().addr("localhost".to_owned()).current_dir("/srv".to_owned()).build().unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.root.as_deref(), Some("/srv"));
+
+    let server = Server::builder().address("example.com".to_owned()).build().unwrap();
+    assert_eq!(server.host, "example.com");
+
+    let point = Point::builder().with_left(1).with_y(2).build();
+    assert_eq!((point.x, point.y), (1, 2));
+}
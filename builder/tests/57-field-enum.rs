@@ -0,0 +1,59 @@
+// With `#[builder(field_enum)]`, `CommandField` has a variant per field,
+// named after it in CamelCase and holding a value of what the field's
+// whole-value setter takes, and `CommandBuilder::set()` sets the field a
+// variant is for. That lets data, like parsed `key=value` pairs, drive the
+// builder while staying typed. Without it, a field may be named `set`.
+
+use derive_builder::Builder;
+use std::time::Duration;
+
+#[derive(Builder)]
+#[builder(field_enum)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    #[builder(default)]
+    timeout: Duration,
+}
+
+#[derive(Builder)]
+#[builder(owned, field_enum)]
+pub struct Wrapper<'a, T> {
+    value: &'a T,
+}
+
+#[derive(Builder)]
+pub struct Flag {
+    set: bool,
+    name: String,
+}
+
+fn parse(key: &str, value: &str) -> CommandField {
+    match key {
+        "executable" => CommandField::Executable(value.to_owned()),
+        "args" => CommandField::Args(value.split(' ').map(str::to_owned).collect()),
+        "current_dir" => CommandField::CurrentDir(value.to_owned()),
+        "timeout" => CommandField::Timeout(Duration::from_secs(value.parse().unwrap())),
+        _ => panic!("unknown key {}", key),
+    }
+}
+
+fn main() {
+    let mut builder = Command::builder();
+    for (key, value) in [("executable", "cargo"), ("args", "build --release"), ("timeout", "5")] {
+        builder.set(parse(key, value));
+    }
+    let command = builder.build().unwrap();
+    assert_eq!(command.executable, "cargo");
+    assert_eq!(command.args, ["build", "--release"]);
+    assert_eq!(command.current_dir, None);
+    assert_eq!(command.timeout, Duration::from_secs(5));
+
+    let wrapper = Wrapper::builder().set(WrapperField::Value(&1)).build().unwrap();
+    assert_eq!(*wrapper.value, 1);
+
+    let flag = Flag::builder().set(true).name("verbose".to_owned()).build().unwrap();
+    assert!(flag.set);
+}
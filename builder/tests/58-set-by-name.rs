@@ -0,0 +1,56 @@
+// `set_by_name()` sets a field by its name from a string, parsed with
+// `FromStr`, for loading configuration. An `each` field gets the parsed item
+// added. Unknown names and values that don't parse are errors. A field whose
+// type isn't `FromStr` opts out with `by_name = false`, which would otherwise
+// keep `set_by_name()` from being called at all. Borrowed fields are left out.
+
+use derive_builder::Builder;
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Builder)]
+pub struct Server {
+    host: String,
+    port: u16,
+    #[builder(each = "include")]
+    includes: Vec<PathBuf>,
+    #[builder(default)]
+    workers: usize,
+    r#type: Option<String>,
+    #[builder(default, by_name = false)]
+    timeout: Duration,
+}
+
+#[derive(Builder)]
+pub struct Route<'a, 'b> {
+    path: &'a str,
+    method: &'b str,
+    weight: u8,
+}
+
+fn main() {
+    let mut builder = Server::builder();
+    let config = [("host", "localhost"), ("port", "8080"), ("includes", "a.conf"), ("includes", "b.conf"), ("type", "http")];
+    for (name, value) in config {
+        builder.set_by_name(name, value).unwrap();
+    }
+
+    assert_eq!(builder.set_by_name("port", "http").err(), Some(ServerBuilderSetError::Invalid("port")));
+    assert_eq!(builder.set_by_name("timeout", "5").err(), Some(ServerBuilderSetError::Unknown));
+    builder.timeout(Duration::from_secs(5));
+
+    let server = builder.build().unwrap();
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.includes, [PathBuf::from("a.conf"), PathBuf::from("b.conf")]);
+    assert_eq!(server.workers, 0);
+    assert_eq!(server.r#type.as_deref(), Some("http"));
+    assert_eq!(server.timeout, Duration::from_secs(5));
+
+    let mut builder = Route::builder();
+    builder.path("/").method("GET");
+    assert_eq!(builder.set_by_name("path", "/index").err(), Some(RouteBuilderSetError::Unknown));
+    builder.set_by_name("weight", "3").unwrap();
+    let route = builder.build().unwrap();
+    assert_eq!((route.path, route.method, route.weight), ("/", "GET", 3));
+}
@@ -0,0 +1,49 @@
+// `#[builder(const)]` makes `Foo::builder()`, the setters and `build()`
+// `const fn`s, so a whole configuration can be built in a `const` item. It
+// implies `owned`. The fields have to be ones a `const fn` can move around,
+// like `Copy` types, and their setters take them as they are. A `validate`
+// function has to be a `const fn` too.
+
+use derive_builder::Builder;
+
+const fn check(limits: &Limits) -> Result<(), &'static str> {
+    if limits.min > limits.max {
+        return Err("min > max");
+    }
+    Ok(())
+}
+
+#[derive(Builder, Debug)]
+#[builder(const)]
+pub struct Config {
+    name: &'static str,
+    retries: u32,
+    #[builder(default = "30")]
+    timeout: u64,
+    verbose: Option<bool>,
+}
+
+#[derive(Builder)]
+#[builder(const, validate = "check")]
+pub struct Limits {
+    min: u32,
+    max: u32,
+}
+
+const CONFIG: Config = match Config::builder().name("server").retries(3).build() {
+    Some(config) => config,
+    None => panic!("incomplete config"),
+};
+
+const MISSING: Option<Config> = Config::builder().name("server").build();
+
+fn main() {
+    assert_eq!(CONFIG.name, "server");
+    assert_eq!(CONFIG.retries, 3);
+    assert_eq!(CONFIG.timeout, 30);
+    assert_eq!(CONFIG.verbose, None);
+    assert!(MISSING.is_none());
+
+    let limits = Limits::builder().min(1).max(2).clear().min(3).max(2).build();
+    assert!(matches!(limits, Err(LimitsBuilderError::Invalid("min > max"))));
+}
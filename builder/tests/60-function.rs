@@ -0,0 +1,47 @@
+// `#[builder]` on a function turns its parameters into the fields of a
+// struct `ConnectArgs`, and `connect()` returns that struct's builder. Its
+// `call()` calls the function with the arguments set, returning `None` if
+// one is missing. The parameters take `#[builder(...)]` as fields do, and
+// the attribute's own arguments are those of the struct's.
+
+use derive_builder::builder;
+
+/// Opens a connection.
+#[builder]
+pub fn connect(
+    host: String,
+    port: u16,
+    #[builder(default = "30")] timeout: u64,
+    retries: Option<u32>,
+    #[builder(each = "protocol")] protocols: Vec<String>,
+) -> String {
+    format!("{}:{} timeout={} retries={:?} {:?}", host, port, timeout, retries, protocols)
+}
+
+#[builder(typestate)]
+fn area<T: std::ops::Mul<Output = T>>(width: T, height: T) -> T {
+    width * height
+}
+
+#[builder(owned, validate = "check")]
+fn divide(a: i32, b: i32) -> i32 {
+    a / b
+}
+
+fn check(args: &DivideArgs) -> Result<(), &'static str> {
+    if args.b == 0 {
+        return Err("division by zero");
+    }
+    Ok(())
+}
+
+fn main() {
+    let connection = connect().host("localhost".to_owned()).port(8080).protocol("h2".to_owned()).call();
+    assert_eq!(connection.as_deref(), Some(r#"localhost:8080 timeout=30 retries=None ["h2"]"#));
+    assert_eq!(connect().port(8080).call(), None);
+
+    assert_eq!(area().width(3).height(4).call(), 12);
+
+    assert_eq!(divide().a(6).b(3).call().unwrap(), 2);
+    assert!(matches!(divide().a(6).b(0).call(), Err(DivideArgsBuilderError::Invalid("division by zero"))));
+}
@@ -0,0 +1,22 @@
+// A field's `#[deprecated]` is repeated on its setters, so that setting it
+// warns where the builder is used. Denying warnings makes that an error.
+
+#![deny(deprecated)]
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[deprecated(note = "use `command_line`")]
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    #[deprecated(note = "use `cwd`")]
+    current_dir: Option<String>,
+}
+
+fn main() {
+    let _ = Command::builder().executable("cargo".to_owned()).build();
+    let _ = Command::builder().current_dir("..".to_owned());
+    let _ = Command::builder().arg("build".to_owned());
+}
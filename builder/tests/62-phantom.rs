@@ -0,0 +1,31 @@
+// A `PhantomData` field has no setters: the builder fills it in, so it
+// doesn't keep build() from succeeding. That works with `typestate` too.
+
+use derive_builder::Builder;
+use std::marker::PhantomData;
+
+pub struct Meters;
+
+#[derive(Builder)]
+pub struct Length<Unit> {
+    value: f64,
+    unit: PhantomData<Unit>,
+}
+
+#[derive(Builder)]
+#[builder(typestate)]
+pub struct Id<T> {
+    value: u64,
+    marker: PhantomData<fn() -> T>,
+}
+
+fn main() {
+    let length: Length<Meters> = Length::builder().value(1.5).build().unwrap();
+    assert_eq!(length.value, 1.5);
+
+    let longer = LengthBuilder::from(length).value(3.0).build().unwrap();
+    assert_eq!(longer.value, 3.0);
+
+    let id: Id<Meters> = Id::builder().value(7).build();
+    assert_eq!(id.value, 7);
+}
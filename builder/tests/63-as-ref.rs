@@ -0,0 +1,40 @@
+// `#[builder(as_ref)]` on a `String`, `PathBuf`, `OsString` or `Vec<T>` field
+// makes its setter take `impl AsRef<str>`, `AsRef<Path>`, `AsRef<OsStr>` or
+// `AsRef<[T]>`, and store an owned copy, so that call sites can pass
+// borrowed values as they are. It overrides the struct's `into`.
+
+use derive_builder::Builder;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+#[derive(Builder)]
+#[builder(into)]
+pub struct Command {
+    #[builder(as_ref)]
+    executable: String,
+    #[builder(as_ref)]
+    current_dir: Option<PathBuf>,
+    #[builder(as_ref)]
+    arg0: OsString,
+    #[builder(as_ref)]
+    stdin: Vec<u8>,
+    timeout: u64,
+}
+
+fn main() {
+    let name = String::from("cargo");
+    let command = Command::builder()
+        .executable(&name)
+        .current_dir(Path::new("/tmp"))
+        .arg0("cargo")
+        .stdin(b"input")
+        .timeout(5u32)
+        .build()
+        .unwrap();
+
+    assert_eq!(command.executable, name);
+    assert_eq!(command.current_dir, Some(PathBuf::from("/tmp")));
+    assert_eq!(command.arg0, "cargo");
+    assert_eq!(command.stdin, b"input");
+    assert_eq!(command.timeout, 5);
+}
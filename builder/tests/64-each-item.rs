@@ -0,0 +1,46 @@
+// The `each` setter of a std collection takes its item type, so integer
+// literals and the like are inferred as the item, and a wrong argument is
+// reported as a plain type mismatch. A collection of another type keeps the
+// generic setter taking anything the collection can be extended with.
+
+use derive_builder::Builder;
+use std::collections::{BTreeSet, VecDeque};
+
+#[derive(Debug, Default)]
+pub struct Text(String);
+
+impl<'a> Extend<&'a str> for Text {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|s| self.0.push_str(s));
+    }
+}
+
+#[derive(Builder)]
+pub struct Job {
+    #[builder(each = "id")]
+    ids: Vec<u64>,
+    #[builder(each = "retry")]
+    retries: VecDeque<u8>,
+    #[builder(each = "tag")]
+    tags: BTreeSet<String>,
+    #[builder(each = "line")]
+    text: Text,
+}
+
+fn main() {
+    let job = Job::builder()
+        .id(1)
+        .id(2)
+        .retry(3)
+        .tag("b".to_owned())
+        .tag("a".to_owned())
+        .line("hello, ")
+        .line("world")
+        .build()
+        .unwrap();
+
+    assert_eq!(job.ids, [1, 2]);
+    assert_eq!(job.retries, [3]);
+    assert_eq!(job.tags.into_iter().collect::<Vec<_>>(), ["a", "b"]);
+    assert_eq!(job.text.0, "hello, world");
+}
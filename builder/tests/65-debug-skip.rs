@@ -0,0 +1,19 @@
+// When the struct also derives CustomDebug, the builder's Debug leaves out
+// the fields it skips with `#[debug(skip)]`, ending in `..` as it does.
+
+use derive_builder::Builder;
+use derive_debug::CustomDebug;
+
+#[derive(Builder, CustomDebug)]
+pub struct Connection {
+    host: String,
+    #[debug(skip)]
+    buffer: Vec<u8>,
+}
+
+fn main() {
+    let mut builder = Connection::builder();
+    builder.host("localhost".to_owned()).buffer(vec![0; 16]);
+    assert_eq!(format!("{:?}", builder), r#"ConnectionBuilder { host: Some("localhost"), .. }"#);
+    assert_eq!(format!("{:?}", builder.build().unwrap()), r#"Connection { host: "localhost", .. }"#);
+}
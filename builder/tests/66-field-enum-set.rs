@@ -0,0 +1,19 @@
+// `set()` of `#[builder(field_enum)]` would clash with the setter of a field
+// named `set`; a `setter_prefix` gives the setters other names.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(field_enum)]
+pub struct Flag {
+    set: bool,
+    name: String,
+}
+
+#[derive(Builder)]
+#[builder(field_enum, setter_prefix = "with_")]
+pub struct Prefixed {
+    set: bool,
+}
+
+fn main() {}
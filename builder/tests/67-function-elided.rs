@@ -0,0 +1,27 @@
+// Parameters of a `#[builder]` function may borrow with elided lifetimes and
+// take `impl Trait`: the arguments struct gets a lifetime or type parameter
+// for each. Those of `fn(&str)` stay the function pointer's own.
+
+use derive_builder::builder;
+use std::fmt::Display;
+
+#[builder]
+fn greet(name: &str, title: Option<&'_ str>, punctuation: impl Display, words: &[impl AsRef<str>], measure: fn(&str) -> usize) -> String {
+    let words = words.iter().map(AsRef::as_ref).collect::<Vec<_>>().join(" ");
+    let greeting = match title {
+        Some(title) => format!("{} {} {}{}", words, title, name, punctuation),
+        None => format!("{} {}{}", words, name, punctuation),
+    };
+    format!("{} ({})", greeting, measure(&greeting))
+}
+
+fn main() {
+    let name = String::from("Ferris");
+    let words = ["hello", "there"];
+    let greeting = greet().name(&name).punctuation('!').words(&words).measure(str::len).call();
+    assert_eq!(greeting.as_deref(), Some("hello there Ferris! (19)"));
+
+    let words = vec![String::from("hi")];
+    let greeting = greet().name("crab").title("Dr.").punctuation(1).words(&words).measure(|s| s.split(' ').count()).call();
+    assert_eq!(greeting.as_deref(), Some("hi Dr. crab1 (3)"));
+}
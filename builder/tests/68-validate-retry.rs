@@ -0,0 +1,45 @@
+// A borrowed builder whose value `validate` rejects gets the values back, so
+// that the caller can fix a field and build again. A field left to its default
+// stays unset. A `build()` that succeeds leaves the builder empty.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+#[builder(validate = "Request::check")]
+pub struct Request {
+    url: String,
+    #[builder(default = "3")]
+    retries: u8,
+    #[builder(default)]
+    timeout: u32,
+    proxy: Option<String>,
+    #[builder(each = "header")]
+    headers: Vec<String>,
+}
+
+impl Request {
+    fn check(&self) -> Result<(), &'static str> {
+        if self.url.starts_with("https://") { Ok(()) } else { Err("not https") }
+    }
+}
+
+fn main() {
+    let mut builder = Request::builder();
+    builder.url("http://example.com".to_owned()).timeout(10).proxy("proxy".to_owned()).header("a: b".to_owned());
+    assert!(matches!(builder.build(), Err(RequestBuilderError::Invalid("not https"))));
+
+    assert_eq!(builder.get_url().map(String::as_str), Some("http://example.com"));
+    assert_eq!(builder.get_retries(), None);
+    assert_eq!(builder.get_timeout(), Some(&10));
+    assert_eq!(builder.get_proxy().map(String::as_str), Some("proxy"));
+    assert_eq!(builder.get_headers(), &["a: b"]);
+
+    let request = builder.url("https://example.com".to_owned()).build().unwrap();
+    assert_eq!(request.url, "https://example.com");
+    assert_eq!((request.retries, request.timeout), (3, 10));
+    assert_eq!(request.proxy.as_deref(), Some("proxy"));
+    assert_eq!(request.headers, ["a: b"]);
+
+    assert!(matches!(builder.build(), Err(RequestBuilderError::Missing("url"))));
+    assert!(builder.get_headers().is_empty());
+}
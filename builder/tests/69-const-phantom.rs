@@ -0,0 +1,23 @@
+// A `PhantomData` field doesn't keep a builder from being `const`: the
+// builder starts it out as `PhantomData` rather than calling `Default`.
+
+use derive_builder::Builder;
+use std::marker::PhantomData;
+
+pub struct Meters;
+
+#[derive(Builder)]
+#[builder(const)]
+pub struct Cfg<T> {
+    a: u32,
+    _m: PhantomData<T>,
+}
+
+const CFG: Cfg<Meters> = match Cfg::builder().a(3).build() {
+    Some(cfg) => cfg,
+    None => panic!("incomplete config"),
+};
+
+fn main() {
+    assert_eq!(CFG.a, 3);
+}
@@ -10,4 +10,64 @@ fn tests() {
     t.pass("tests/07-repeated-field.rs");
     t.compile_fail("tests/08-unrecognized-attribute.rs");
     t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-no-implicit-prelude.rs");
+    t.compile_fail("tests/11-debug-expand.rs");
+    t.compile_fail("tests/12-duplicate-attribute.rs");
+    t.compile_fail("tests/13-deprecated-each.rs");
+    t.pass("tests/14-custom-debug.rs");
+    t.pass("tests/15-generics.rs");
+    t.pass("tests/16-default.rs");
+    t.compile_fail("tests/17-default-misplaced.rs");
+    t.compile_fail("tests/18-default-expr-wrong.rs");
+    t.pass("tests/19-into.rs");
+    t.compile_fail("tests/20-into-each.rs");
+    t.pass("tests/21-typestate.rs");
+    t.compile_fail("tests/22-typestate-missing.rs");
+    t.pass("tests/23-owned.rs");
+    t.pass("tests/24-validate.rs");
+    t.pass("tests/25-map-each.rs");
+    t.pass("tests/26-docs.rs");
+    t.pass("tests/27-custom-field.rs");
+    t.compile_fail("tests/28-custom-field-wrong.rs");
+    t.pass("tests/29-references.rs");
+    t.pass("tests/30-no-std.rs");
+    t.pass("tests/31-build-partial.rs");
+    t.pass("tests/32-derive.rs");
+    t.pass("tests/33-each-whole.rs");
+    t.pass("tests/34-qualified-option.rs");
+    t.compile_fail("tests/35-optional-wrong.rs");
+    t.pass("tests/36-const-generics.rs");
+    t.pass("tests/37-try-from.rs");
+    t.pass("tests/38-unset.rs");
+    t.pass("tests/39-setter-prefix.rs");
+    t.pass("tests/40-getters.rs");
+    t.pass("tests/41-wrap.rs");
+    t.compile_fail("tests/42-wrap-wrong.rs");
+    t.pass("tests/43-reexport.rs");
+    t.pass("tests/44-no-clone.rs");
+    t.pass("tests/45-cfg.rs");
+    t.compile_fail("tests/46-unknown-key.rs");
+    t.pass("tests/47-serde.rs");
+    t.pass("tests/48-bare-each.rs");
+    t.pass("tests/49-extend.rs");
+    t.pass("tests/50-custom-setter.rs");
+    t.pass("tests/51-to-builder.rs");
+    t.pass("tests/52-each-option.rs");
+    t.pass("tests/53-module.rs");
+    t.pass("tests/54-no-strip-option.rs");
+    t.pass("tests/55-sensitive.rs");
+    t.pass("tests/56-alias.rs");
+    t.pass("tests/57-field-enum.rs");
+    t.pass("tests/58-set-by-name.rs");
+    t.pass("tests/59-const.rs");
+    t.pass("tests/60-function.rs");
+    t.compile_fail("tests/61-deprecated.rs");
+    t.pass("tests/62-phantom.rs");
+    t.pass("tests/63-as-ref.rs");
+    t.pass("tests/64-each-item.rs");
+    t.pass("tests/65-debug-skip.rs");
+    t.compile_fail("tests/66-field-enum-set.rs");
+    t.pass("tests/67-function-elided.rs");
+    t.pass("tests/68-validate-retry.rs");
+    t.pass("tests/69-const-phantom.rs");
 }
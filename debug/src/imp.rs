@@ -1,67 +1,67 @@
 use proc_macro2::TokenStream;
 use quote::quote;
-use syn::{Data, DeriveInput, Field, GenericArgument, Ident, LitStr, Token, Type, WhereClause, WherePredicate, parse_quote};
-use syn::parse::{Parse, ParseStream};
+use attrs::Args;
+use syn::{Data, DeriveInput, Field, Fields, GenericArgument, Index, LitStr, Member, Type, WhereClause, WherePredicate, parse_quote};
 use syn::visit::{self, Visit};
 
 #[derive(Default)]
 struct DebugAttr {
-    bound: Option<LitStr>
+    bound: Option<LitStr>,
+    debug_expand: bool,
 }
 
 impl DebugAttr {
     fn from_derive_input(input: &DeriveInput) -> syn::Result<Self> {
-        for attr in &input.attrs {
-            if attr.path.is_ident("debug") {
-                return attr.parse_args();
-            }
-        }
-        Ok(Default::default())
-    }
-}
-
-impl Parse for DebugAttr {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
         let mut bound = None;
-
-        while input.peek(Ident) {
-            let name = input.parse::<Ident>()?;
-            if name == "bound" {
-                input.parse::<Token![=]>()?;
-                bound = input.parse()?;
+        let mut debug_expand = None;
+        for arg in Args::from_attrs("debug", &input.attrs)? {
+            if arg.key == "bound" {
+                attrs::set(&mut bound, &arg, arg.lit_str()?.clone())?;
+            } else if arg.key == "debug_expand" {
+                arg.flag()?;
+                attrs::set(&mut debug_expand, &arg, ())?;
+            } else {
+                return Err(syn::Error::new_spanned(arg, r#"expected `debug(bound = "...")` or `debug(debug_expand)`"#));
             }
         }
 
         Ok(Self {
             bound,
+            debug_expand: debug_expand.is_some(),
         })
     }
 }
 
-struct FieldDebugAttr(String);
-
-impl Default for FieldDebugAttr {
-    fn default() -> Self {
-        Self("{:?}".into())
-    }
+// `#[debug = "..."]`, `#[debug(skip)]` or `#[debug(bound = "...")]` on a
+// field.
+struct FieldDebugAttr {
+    format: String,
+    skip: bool,
+    bound: Option<LitStr>,
 }
 
 impl FieldDebugAttr {
     fn from_field(field: &Field) -> syn::Result<Self> {
-        for attr in &field.attrs {
-            if attr.path.is_ident("debug") {
-                return Ok(syn::parse2(attr.tokens.clone())?);
+        let mut format = None;
+        let mut skip = None;
+        let mut bound = None;
+        for arg in Args::from_attrs("debug", &field.attrs)? {
+            if arg.key == "bound" {
+                attrs::set(&mut bound, &arg, arg.lit_str()?.clone())?;
+            } else if arg.key == "skip" {
+                arg.flag()?;
+                attrs::set(&mut skip, &arg, ())?;
+            } else if arg.key == "debug" {
+                attrs::set(&mut format, &arg, arg.lit_str()?.value())?;
+            } else {
+                return Err(syn::Error::new_spanned(arg, r#"expected `#[debug = "..."]`, `#[debug(skip)]` or `#[debug(bound = "...")]`"#));
             }
         }
-        Ok(Default::default())
-    }
-}
-
-impl Parse for FieldDebugAttr {
-    fn parse(input: ParseStream) -> syn::Result<Self> {
-        input.parse::<Token![=]>()?;
-        let val = input.parse::<LitStr>()?.value();
-        Ok(Self(val))
+        Ok(Self {
+            format: format.unwrap_or_else(|| "{:?}".into()),
+            skip: skip.is_some(),
+            bound,
+        })
     }
 }
 
@@ -82,7 +82,7 @@ impl<'ast, 'b> Visit<'ast> for IsPhantomData<'ast, 'b> {
     }
 }
 
-struct CollectPhantomDataT<'a, 'b>(&'b mut Vec<&'a Type>, bool);
+struct CollectPhantomDataT<'a, 'b>(&'b mut Vec<&'a Type>);
 
 impl<'a, 'b> Visit<'a> for CollectPhantomDataT<'a, 'b> {
     fn visit_type(&mut self, i: &'a Type) {
@@ -108,78 +108,116 @@ impl<'ast, 'b> Visit<'ast> for CollectFieldTypes<'ast, 'b> {
     fn visit_type(&mut self, i: &'ast Type) {
         let mut has_genric_argument = false;
         HasGenericArgument(&mut has_genric_argument).visit_type(i);
-        if !has_genric_argument && !self.1.contains(&&i) {
+        if !has_genric_argument && !self.1.contains(&i) {
             self.0.push(i);
         }
         visit::visit_type(self, i);
     }
 }
 
-struct TargetField<'a> {
-    ident: &'a Ident,
+struct TargetField {
+    // The field's name, or its position in a tuple struct.
+    member: Member,
     debug: String,
+    // `#[debug(skip)]`: left out, and the output marked as non-exhaustive.
+    skip: bool,
+    // `#[debug(bound = "...")]`: replaces the bounds inferred from the
+    // field's type.
+    bound: Option<WherePredicate>,
 }
 
-impl<'a> TargetField<'a> {
-    fn from(field: &'a Field) -> syn::Result<Self> {
-        let ident = field.ident.as_ref().unwrap();
-        let FieldDebugAttr(debug) = FieldDebugAttr::from_field(field)?;
+impl TargetField {
+    fn from(index: usize, field: &Field) -> syn::Result<Self> {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        };
+        let FieldDebugAttr { format: debug, skip, bound } = FieldDebugAttr::from_field(field)?;
+        let bound = match bound {
+            Some(bound) => Some(bound.parse::<WherePredicate>()?),
+            None => None,
+        };
         Ok(Self {
-            ident,
+            member,
             debug,
+            skip,
+            bound,
         })
     }
-
-    fn ident_str(&self) -> String {
-        self.ident.to_string()
-    }
 }
 
 fn debug(input: &DeriveInput) -> syn::Result<TokenStream> {
     let ident = &input.ident;
     let ident_str = ident.to_string();
 
-    let fields = if let DeriveInput { data: Data::Struct(data), .. } = input {
-        data.fields.iter().map(TargetField::from).collect::<syn::Result<Vec<_>>>()?
+    let (data, fields) = if let DeriveInput { data: Data::Struct(data), .. } = input {
+        (data, data.fields.iter().enumerate().map(|(i, f)| TargetField::from(i, f)).collect::<syn::Result<Vec<_>>>()?)
     } else {
         return Err(syn::Error::new_spanned(input, "enum or union not supported."));
     };
-
-    let where_clause = if let DebugAttr { bound: Some(bound) } = DebugAttr::from_derive_input(input)? {
-        let predicate = syn::parse_str::<WherePredicate>(&bound.value())?;
-        Some(parse_quote! {
-            where #predicate
-        })
+    let tuple = matches!(data.fields, Fields::Unnamed(..));
+
+    // The struct's `bound` replaces all inference, a field's only that from
+    // its own type.
+    let attr = DebugAttr::from_derive_input(input)?;
+    let mut predicates = fields.iter().filter_map(|f| f.bound.clone()).collect::<Vec<WherePredicate>>();
+    if let Some(bound) = &attr.bound {
+        predicates.push(syn::parse_str::<WherePredicate>(&bound.value())?);
     } else {
+        // Only the fields that are printed need their types to be `Debug`.
+        let printed = data.fields.iter().zip(&fields).filter(|(_, f)| !f.skip).collect::<Vec<_>>();
         let mut phantom_ts = vec![];
-        CollectPhantomDataT(&mut phantom_ts, false).visit_derive_input(input);
+        let mut phantom = CollectPhantomDataT(&mut phantom_ts);
+        for (field, _) in &printed {
+            phantom.visit_field(field);
+        }
         let mut generic_types = vec![];
-        CollectFieldTypes(&mut generic_types, phantom_ts).visit_derive_input(input);
-        let where_clause = generic_types.into_iter().map(|g| {
-            parse_quote! { #g: ::std::fmt::Debug }
-        }).collect::<Vec<WherePredicate>>();
-        if !where_clause.is_empty() {
-            Option::<WhereClause>::Some(parse_quote! { where #(#where_clause),* })
-        } else {
-            None
+        let mut collect = CollectFieldTypes(&mut generic_types, phantom_ts);
+        for (field, target) in &printed {
+            if target.bound.is_none() {
+                collect.visit_field(field);
+            }
         }
+        predicates.extend(generic_types.into_iter().map(|g| -> WherePredicate {
+            parse_quote! { #g: ::core::fmt::Debug }
+        }));
+    }
+    let where_clause = if !predicates.is_empty() {
+        Option::<WhereClause>::Some(parse_quote! { where #(#predicates),* })
+    } else {
+        None
     };
 
-    let field_names = fields.iter().map(|f| f.ident).collect::<Vec<_>>();
-    let field_strs = fields.iter().map(TargetField::ident_str).collect::<Vec<_>>();
-    let debugs = fields.iter().map(|f| &f.debug).collect::<Vec<_>>();
+    // A tuple struct's fields are listed by position, as `debug_tuple` does.
+    let entries = fields.iter().filter(|f| !f.skip).map(|f| {
+        let (member, debug) = (&f.member, &f.debug);
+        let value = quote!(&::core::format_args!(#debug, &self.#member));
+        match member {
+            Member::Named(ident) => {
+                let name = ident.to_string();
+                quote!(.field(#name, #value))
+            }
+            Member::Unnamed(_) => quote!(.field(#value)),
+        }
+    });
+    let start = if tuple { quote!(debug_tuple) } else { quote!(debug_struct) };
+    let finish = if fields.iter().any(|f| f.skip) { quote!(finish_non_exhaustive) } else { quote!(finish) };
 
     let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
 
-    Ok(quote! {
-        impl #impl_generics ::std::fmt::Debug for #ident #ty_generics #where_clause {
-            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-                f.debug_struct(#ident_str)
-                    #(.field(#field_strs, &format_args!(#debugs, &self.#field_names)))*
-                    .finish()
+    let tokens = quote! {
+        impl #impl_generics ::core::fmt::Debug for #ident #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                f.#start(#ident_str)
+                    #(#entries)*
+                    .#finish()
             }
         }
-    })
+    };
+    if attr.debug_expand {
+        attrs::debug_expand(&tokens);
+    }
+    Ok(tokens)
 }
 
 pub fn derive(input: TokenStream) -> TokenStream {
@@ -189,6 +227,6 @@ pub fn derive(input: TokenStream) -> TokenStream {
     };
     match debug(&input) {
         Ok(token) => token,
-        Err(err) => return err.to_compile_error(),
+        Err(err) => err.to_compile_error(),
     }
 }
@@ -0,0 +1,23 @@
+// The expansion names everything through absolute `::core` paths, so it
+// compiles in a module without the prelude and next to a local `std`.
+
+#![no_implicit_prelude]
+
+use ::derive_debug::CustomDebug;
+
+#[allow(dead_code)]
+mod std {}
+
+#[derive(CustomDebug)]
+pub struct Field<T> {
+    name: &'static str,
+    #[debug = "0b{:08b}"]
+    bitmask: u8,
+    value: T,
+}
+
+fn main() {
+    let f = Field { name: "F", bitmask: 0b00011100, value: 1 };
+    let debug = ::std::format!("{:?}", f);
+    ::std::assert_eq!(debug, r#"Field { name: "F", bitmask: 0b00011100, value: 1 }"#);
+}
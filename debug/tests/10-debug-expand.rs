@@ -0,0 +1,25 @@
+// `#[debug(debug_expand)]` prints the generated impl to stderr while
+// compiling, and can be combined with `bound = "..."`.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+#[debug(debug_expand)]
+pub struct Field {
+    name: &'static str,
+    #[debug = "0b{:08b}"]
+    bitmask: u8,
+}
+
+#[derive(CustomDebug)]
+#[debug(bound = "T: Debug", debug_expand)]
+pub struct Wrapper<T> {
+    value: T,
+}
+
+fn main() {
+    let f = Field { name: "F", bitmask: 0b00011100 };
+    assert_eq!(format!("{:?}", f), r#"Field { name: "F", bitmask: 0b00011100 }"#);
+    assert_eq!(format!("{:?}", Wrapper { value: 1 }), "Wrapper { value: 1 }");
+}
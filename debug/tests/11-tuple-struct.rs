@@ -0,0 +1,21 @@
+// Tuple structs are formatted like `#[derive(Debug)]` does, with the fields
+// listed by position. `#[debug = "..."]` works on positional fields too, and
+// unit structs show their name.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Wrapper(u64, #[debug = "0b{:b}"] u8, String);
+
+#[derive(CustomDebug)]
+pub struct Generic<T>(T);
+
+#[derive(CustomDebug)]
+pub struct Unit;
+
+fn main() {
+    let wrapper = Wrapper(7, 5, "seven".to_owned());
+    assert_eq!(format!("{:?}", wrapper), r#"Wrapper(7, 0b101, "seven")"#);
+    assert_eq!(format!("{:#?}", Generic(1)), "Generic(\n    1,\n)");
+    assert_eq!(format!("{:?}", Unit), "Unit");
+}
@@ -0,0 +1,28 @@
+// `#[debug(skip)]` leaves a field out of the output, e.g. a large buffer or a
+// closure, and the output ends in `..` to show that something was left out.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Connection {
+    host: &'static str,
+    #[debug(skip)]
+    buffer: Vec<u8>,
+    #[debug(skip)]
+    on_close: fn(),
+}
+
+#[derive(CustomDebug)]
+pub struct Handle(u32, #[debug(skip)] fn());
+
+#[derive(CustomDebug)]
+pub struct Plain {
+    id: u32,
+}
+
+fn main() {
+    let connection = Connection { host: "localhost", buffer: vec![0; 4096], on_close: || {} };
+    assert_eq!(format!("{:?}", connection), r#"Connection { host: "localhost", .. }"#);
+    assert_eq!(format!("{:?}", Handle(3, || {})), "Handle(3, ..)");
+    assert_eq!(format!("{:?}", Plain { id: 1 }), "Plain { id: 1 }");
+}
@@ -0,0 +1,45 @@
+// `#[debug(bound = "...")]` on a field replaces only the bounds inferred from
+// that field's type; the other fields keep theirs, here `U: Debug`.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+
+pub trait Trait {
+    type Value;
+}
+
+pub struct Field<T: Trait> {
+    value: T::Value,
+}
+
+impl<T: Trait> Debug for Field<T>
+where
+    T::Value: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Wrapper<T: Trait, U> {
+    #[debug(bound = "T::Value: Debug")]
+    field: Field<T>,
+    normal: U,
+}
+
+fn assert_debug<F: Debug>() {}
+
+struct Id;
+
+impl Trait for Id {
+    type Value = u8;
+}
+
+fn main() {
+    // `Id` itself isn't Debug.
+    assert_debug::<Wrapper<Id, String>>();
+
+    let wrapper = Wrapper::<Id, _> { field: Field { value: 1 }, normal: "two" };
+    assert_eq!(format!("{:?}", wrapper), r#"Wrapper { field: 1, normal: "two" }"#);
+}
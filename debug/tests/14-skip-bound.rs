@@ -0,0 +1,28 @@
+// Bounds are inferred only from the fields that are printed, so a type
+// parameter used only by skipped fields doesn't have to be `Debug`.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+pub struct Cache<K, V> {
+    name: K,
+    #[debug(skip)]
+    entries: Vec<V>,
+}
+
+#[derive(CustomDebug)]
+pub struct Callback<F>(u32, #[debug(skip)] F);
+
+fn assert_debug<F: Debug>() {}
+
+struct NotDebug;
+
+fn main() {
+    assert_debug::<Cache<String, NotDebug>>();
+    assert_debug::<Callback<NotDebug>>();
+
+    let cache = Cache { name: "users", entries: vec![NotDebug] };
+    assert_eq!(format!("{:?}", cache), r#"Cache { name: "users", .. }"#);
+    assert_eq!(format!("{:?}", Callback(1, || {})), "Callback(1, ..)");
+}
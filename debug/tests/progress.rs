@@ -9,4 +9,10 @@ fn tests() {
     t.pass("tests/06-bound-trouble.rs");
     t.pass("tests/07-associated-type.rs");
     t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/09-no-implicit-prelude.rs");
+    t.pass("tests/10-debug-expand.rs");
+    t.pass("tests/11-tuple-struct.rs");
+    t.pass("tests/12-skip.rs");
+    t.pass("tests/13-field-bound.rs");
+    t.pass("tests/14-skip-bound.rs");
 }
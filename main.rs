@@ -39,13 +39,13 @@ fn main() {
 
     // Initialized to all 0 bits.
     let mut entry = RedirectionTableEntry::new();
-    assert_eq!(entry.get_acknowledged(), false);
+    assert!(!entry.get_acknowledged());
     assert_eq!(entry.get_trigger_mode(), TriggerMode::Edge);
     assert_eq!(entry.get_delivery_mode(), DeliveryMode::Fixed);
 
     entry.set_acknowledged(true);
     entry.set_delivery_mode(DeliveryMode::SMI);
-    assert_eq!(entry.get_acknowledged(), true);
+    assert!(entry.get_acknowledged());
     assert_eq!(entry.get_trigger_mode(), TriggerMode::Edge);
     assert_eq!(entry.get_delivery_mode(), DeliveryMode::SMI);
 }
@@ -24,6 +24,7 @@ impl Iterator for Range {
 }
 
 struct Input {
+    debug: bool,
     ident: Ident,
     range: Range,
     tokens: TokenStream,
@@ -31,6 +32,15 @@ struct Input {
 
 impl Parse for Input {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        // `seq!(@debug N in ...)` prints the expansion while compiling.
+        let debug = input.peek(Token![@]);
+        if debug {
+            input.parse::<Token![@]>()?;
+            let name = input.parse::<Ident>()?;
+            if name != "debug" {
+                return Err(syn::Error::new_spanned(name, "expected `@debug`"));
+            }
+        }
         let ident = input.parse()?;
         input.parse::<Token![in]>()?;
         let begin = input.parse::<LitInt>()?;
@@ -46,6 +56,7 @@ impl Parse for Input {
         let group = input.parse::<Group>()?;
         let tokens = group.stream();
         Ok(Self {
+            debug,
             ident,
             range,
             tokens,
@@ -62,13 +73,13 @@ fn expand(ident: &Ident, n: usize, tokens: TokenStream) -> syn::Result<TokenStre
     while let Some(mut tree) = tokens.next() {
         match &tree {
             TokenTree::Group(g) => {
-                tree = Group::new(g.delimiter(), expand(ident, n, g.stream())?).into();
+                let mut group = Group::new(g.delimiter(), expand(ident, n, g.stream())?);
+                group.set_span(g.span());
+                tree = group.into();
             }
 
-            TokenTree::Ident(i) => {
-                if ident == i {
-                    tree = Literal::usize_unsuffixed(n).into();
-                }
+            TokenTree::Ident(i) if ident == i => {
+                tree = Literal::usize_unsuffixed(n).into();
             }
 
             TokenTree::Punct(p) => {
@@ -107,24 +118,23 @@ fn traverse(expanded: &mut bool, ident: &Ident, range: Range, tokens: TokenStrea
     let mut back = None;
 
     while let Some(mut tree) = tokens.next() {
-        match tree {
-            TokenTree::Group(g) => {
-                if let (Some(TokenTree::Punct(b)), Delimiter::Parenthesis, Some(TokenTree::Punct(n))) = (back.as_ref(), g.delimiter(), tokens.peek()) {
-                    if b.as_char() == '#' && n.as_char() == '*' {
-                        tokens.next();
-                        back = None;
-
-                        for n in range.clone() {
-                            let e = expand(ident, n, g.stream())?;
-                            result.extend(e);
-                        }
-                        *expanded = true;
-                        continue;
+        if let TokenTree::Group(g) = tree {
+            if let (Some(TokenTree::Punct(b)), Delimiter::Parenthesis, Some(TokenTree::Punct(n))) = (back.as_ref(), g.delimiter(), tokens.peek()) {
+                if b.as_char() == '#' && n.as_char() == '*' {
+                    tokens.next();
+                    back = None;
+
+                    for n in range.clone() {
+                        let e = expand(ident, n, g.stream())?;
+                        result.extend(e);
                     }
+                    *expanded = true;
+                    continue;
                 }
-                tree = Group::new(g.delimiter(), traverse(expanded, ident, range.clone(), g.stream())?).into();
             }
-            _ => {}
+            let mut group = Group::new(g.delimiter(), traverse(expanded, ident, range.clone(), g.stream())?);
+            group.set_span(g.span());
+            tree = group.into();
         }
 
         if let Some(back) = &mut back {
@@ -143,13 +153,20 @@ fn traverse(expanded: &mut bool, ident: &Ident, range: Range, tokens: TokenStrea
 }
 
 pub fn seq(input: TokenStream) -> TokenStream {
-    let Input { ident, range, tokens } = match syn::parse2(input) {
+    let Input { debug, ident, range, tokens } = match syn::parse2(input) {
         Ok(item) => item,
         Err(err) => return err.to_compile_error(),
     };
+    let result = expand_all(&ident, range, tokens);
+    if debug {
+        attrs::debug_expand(&result);
+    }
+    result
+}
 
+fn expand_all(ident: &Ident, range: Range, tokens: TokenStream) -> TokenStream {
     let mut expanded = false;
-    let body = match traverse(&mut expanded, &ident, range.clone(), tokens.clone()) {
+    let body = match traverse(&mut expanded, ident, range.clone(), tokens.clone()) {
         Ok(body) => body,
         Err(err) => return err.to_compile_error(),
     };
@@ -159,7 +176,7 @@ pub fn seq(input: TokenStream) -> TokenStream {
 
     let mut bodies = vec![];
     for n in range {
-        let body = match expand(&ident, n, tokens.clone()) {
+        let body = match expand(ident, n, tokens.clone()) {
             Ok(body) => body,
             Err(err) => return err.to_compile_error(),
         };
@@ -0,0 +1,15 @@
+// `seq!(@debug ...)` prints the expansion to stderr while compiling, so the
+// generated code can be inspected without cargo-expand. The expansion itself
+// is unchanged.
+
+use seq::seq;
+
+seq!(@debug N in 0..2 {
+    fn f#N () -> u64 {
+        N * 2
+    }
+});
+
+fn main() {
+    assert_eq!(f0() + f1(), 2);
+}
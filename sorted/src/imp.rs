@@ -2,12 +2,14 @@ use std::mem;
 use std::cmp;
 
 use proc_macro2::TokenStream;
+use attrs::Args;
 use syn::{ExprMatch, ItemEnum, ItemFn, Pat, PatTupleStruct, PatStruct, PatPath};
 use syn::visit_mut::{self, VisitMut};
-use syn::spanned::Spanned;
 use quote::ToTokens;
 
-fn sorted_enum(input: &ItemEnum) -> syn::Result<()> {
+// The ordering checks return the misordering, if any, rather than failing, so
+// that `#[sorted(warn)]` can downgrade it.
+fn sorted_enum(input: &ItemEnum) -> Option<syn::Error> {
     let variants = input.variants.iter().collect::<Vec<_>>();
     let mut sorted = variants.clone();
     sorted.sort_by(|l, r| l.ident.cmp(&r.ident));
@@ -16,11 +18,11 @@ fn sorted_enum(input: &ItemEnum) -> syn::Result<()> {
     while let Some((l, r)) = iter.next() {
         if l != r {
             if let Some((_, next)) = iter.peek() {
-                return Err(syn::Error::new_spanned(&r.ident, format!("{} should sort before {}", r.ident, next.ident)));
+                return Some(syn::Error::new_spanned(&r.ident, format!("{} should sort before {}", r.ident, next.ident)));
             }
         }
     }
-    Ok(())
+    None
 }
 
 #[derive(Clone, PartialEq, Eq)]
@@ -45,12 +47,12 @@ impl<'a> PatWrapper<'a> {
     }
 }
 
-impl Spanned for PatWrapper<'_> {
-    fn span(&self) -> proc_macro2::Span {
+impl ToTokens for PatWrapper<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
         match self.0 {
-            Pat::Path(PatPath { path, .. }) | Pat::Struct(PatStruct { path, .. }) | Pat::TupleStruct(PatTupleStruct { path, .. }) => path.span(),
-            Pat::Ident(item) => item.span(),
-            Pat::Wild(item) => item.span(),
+            Pat::Path(PatPath { path, .. }) | Pat::Struct(PatStruct { path, .. }) | Pat::TupleStruct(PatTupleStruct { path, .. }) => path.to_tokens(tokens),
+            Pat::Ident(item) => item.to_tokens(tokens),
+            Pat::Wild(item) => item.to_tokens(tokens),
             _ => unreachable!("{:?}", self.0),
         }
     }
@@ -73,7 +75,7 @@ impl cmp::Ord for PatWrapper<'_> {
     }
 }
 
-fn sorted_match(input: &ExprMatch) -> syn::Result<()> {
+fn sorted_match(input: &ExprMatch) -> syn::Result<Option<syn::Error>> {
     let arms = input.arms.iter().map(|a| PatWrapper::try_from(&a.pat)).collect::<syn::Result<Vec<_>>>()?;
     let mut sorted = arms.clone();
     sorted.sort();
@@ -82,39 +84,53 @@ fn sorted_match(input: &ExprMatch) -> syn::Result<()> {
     while let Some((l, r)) = iter.next() {
         if l != r {
             if let Some((_, next)) = iter.peek() {
-                return Err(syn::Error::new(r.span(), format!("{} should sort before {}", r.ident(), next.ident())));
+                return Ok(Some(syn::Error::new_spanned(r, format!("{} should sort before {}", r.ident(), next.ident()))));
             }
         }
     }
-    Ok(())
+    Ok(None)
 }
 
-fn try_sorted(attr: TokenStream, input: TokenStream) -> syn::Result<()> {
-    match syn::parse2::<ItemEnum>(input.clone()) {
-        Ok(item) => {
-            sorted_enum(&item)?;
-            return Ok(());
+// `#[sorted(warn)]` reports a misordering as a warning instead of an error.
+fn warn_arg(args: Args) -> syn::Result<bool> {
+    let mut warn = None;
+    for arg in args {
+        if arg.key != "warn" {
+            return Err(syn::Error::new_spanned(arg, "expected `warn`"));
         }
-        Err(..) => {}
-    };
-    match syn::parse2::<ExprMatch>(input.clone()) {
-        Ok(item) => {
-            sorted_match(&item)?;
-            return Ok(());
+        arg.flag()?;
+        attrs::set(&mut warn, &arg, ())?;
+    }
+    Ok(warn.is_some())
+}
+
+// Returns the tokens of any warning, to be emitted next to the input.
+fn try_sorted(attr: TokenStream, warn: bool, input: TokenStream) -> syn::Result<TokenStream> {
+    let misordered = if let Ok(item) = syn::parse2::<ItemEnum>(input.clone()) {
+        sorted_enum(&item)
+    } else {
+        match syn::parse2::<ExprMatch>(input) {
+            Ok(item) => sorted_match(&item)?,
+            Err(..) => return Err(syn::Error::new_spanned(attr, "expected enum or match expression")),
         }
-        Err(..) => Err(syn::Error::new_spanned(attr, "expected enum or match expression"))
+    };
+    match misordered {
+        Some(err) if warn => Ok(attrs::warning(err.span(), &err.to_string())),
+        Some(err) => Err(err),
+        None => Ok(TokenStream::new()),
     }
 }
 
 pub fn sorted(attr: TokenStream, input: TokenStream) -> TokenStream {
-    if let Err(err) = try_sorted(attr, input.clone()) {
-        err.to_compile_error().into_iter().chain(input).collect()
-    } else {
-         input
+    let result = syn::parse2(attr.clone()).and_then(warn_arg).and_then(|warn| try_sorted(attr, warn, input.clone()));
+    match result {
+        Ok(warning) => warning.into_iter().chain(input).collect(),
+        Err(err) => err.to_compile_error().into_iter().chain(input).collect(),
     }
 }
 
-struct SortedVisitor<'a>(&'a mut Vec<syn::Error>);
+// Collects errors and warnings, emitted ahead of the checked function.
+struct SortedVisitor<'a>(&'a mut Vec<TokenStream>);
 
 impl<'a> VisitMut for SortedVisitor<'a> {
     fn visit_expr_match_mut(&mut self, i: &mut ExprMatch) {
@@ -130,8 +146,10 @@ impl<'a> VisitMut for SortedVisitor<'a> {
         mem::swap(&mut newattrs, &mut i.attrs);
 
         if let Some(attr) = found {
-            if let Err(err) = try_sorted(attr.into_token_stream(), i.into_token_stream()) {
-                self.0.push(err);
+            let warn = if attr.tokens.is_empty() { Ok(false) } else { attr.parse_args().and_then(warn_arg) };
+            match warn.and_then(|warn| try_sorted(attr.into_token_stream(), warn, i.into_token_stream())) {
+                Ok(warning) => self.0.push(warning),
+                Err(err) => self.0.push(err.to_compile_error()),
             }
         }
 
@@ -140,12 +158,33 @@ impl<'a> VisitMut for SortedVisitor<'a> {
 }
 
 
-pub fn check(_: TokenStream, input: TokenStream) -> TokenStream {
+fn check_args(args: TokenStream) -> syn::Result<bool> {
+    let mut debug_expand = None;
+    for arg in syn::parse2::<Args>(args)? {
+        if arg.key != "debug_expand" {
+            return Err(syn::Error::new_spanned(arg, "expected `debug_expand`"));
+        }
+        arg.flag()?;
+        attrs::set(&mut debug_expand, &arg, ())?;
+    }
+    Ok(debug_expand.is_some())
+}
+
+pub fn check(args: TokenStream, input: TokenStream) -> TokenStream {
+    // `#[sorted::check(debug_expand)]` prints the expansion while compiling.
+    let debug_expand = match check_args(args) {
+        Ok(debug_expand) => debug_expand,
+        Err(err) => return err.to_compile_error().into_iter().chain(input).collect(),
+    };
     match syn::parse2::<ItemFn>(input) {
         Ok(mut item) => {
             let mut errors = vec![];
             SortedVisitor(&mut errors).visit_item_fn_mut(&mut item);
-            errors.into_iter().flat_map(|err| err.to_compile_error().into_iter()).chain(item.to_token_stream()).collect()
+            let tokens = errors.into_iter().flatten().chain(item.to_token_stream()).collect::<TokenStream>();
+            if debug_expand {
+                attrs::debug_expand(&tokens);
+            }
+            tokens
         }
         Err(err) => err.to_compile_error(),
     }
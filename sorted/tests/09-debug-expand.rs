@@ -0,0 +1,26 @@
+// `#[sorted::check(debug_expand)]` prints the rewritten function to stderr
+// while compiling, with its `#[sorted]` attributes removed. Other arguments
+// are rejected, keeping the function so that its callers still compile.
+
+pub enum Conference {
+    RustConf,
+    RustFest,
+}
+
+#[sorted::check(debug_expand)]
+pub fn region(conf: &Conference) -> &str {
+    use self::Conference::*;
+
+    #[sorted]
+    match conf {
+        RustConf => "North America",
+        RustFest => "Europe",
+    }
+}
+
+#[sorted::check(verbose)]
+pub fn other() {}
+
+fn main() {
+    other();
+}
@@ -0,0 +1,37 @@
+// `#[sorted(warn)]` reports a misordering as a warning and lets the build
+// continue. On a nightly compiler with the `nightly` feature the warning is a
+// proper diagnostic; on stable it shows up as the note of a `deprecated`
+// warning at the same place.
+//
+// Anything other than `warn` is still rejected.
+
+use sorted::sorted;
+
+#[sorted(warn)]
+pub enum Conference {
+    RustBeltRust,
+    RustConf,
+    RustFest,
+    RustLatam,
+    RustRush,
+    EuroRust,
+}
+
+#[sorted::check]
+fn describe(conference: &Conference) -> &'static str {
+    use self::Conference::*;
+
+    #[sorted(warn)]
+    match conference {
+        RustFest => "Europe",
+        RustConf => "North America",
+        _ => "elsewhere",
+    }
+}
+
+#[sorted(warn, quiet)]
+pub enum Empty {}
+
+fn main() {
+    describe(&Conference::RustConf);
+}
@@ -9,4 +9,6 @@ fn tests() {
     t.compile_fail("tests/06-pattern-path.rs");
     t.compile_fail("tests/07-unrecognized-pattern.rs");
     t.pass("tests/08-underscore.rs");
+    t.compile_fail("tests/09-debug-expand.rs");
+    t.compile_fail("tests/10-warn.rs");
 }